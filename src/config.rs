@@ -0,0 +1,96 @@
+use std::{fs, path::Path};
+
+use miden_client::account::AccountId;
+use miden_crypto::{Felt, Word};
+use serde::Deserialize;
+
+/// Registry settings applied by `deploy` after the contract is initialized.
+#[derive(Debug, Deserialize)]
+pub struct DeployConfig {
+    pub prices: Vec<PriceConfig>,
+    /// Seconds after expiry during which a domain can still be renewed for its previous owner but not registered.
+    #[serde(default)]
+    pub grace_period: u32,
+    pub parameters: RegistryParameters,
+}
+
+/// Registry limits set by `init` and `set_parameters`, stored in the parameters slot (13).
+#[derive(Debug, Clone, Copy, Deserialize)]
+pub struct RegistryParameters {
+    /// Seconds in a registration year.
+    pub one_year: u32,
+    #[serde(default = "default_max_registration_years")]
+    pub max_registration_years: u32,
+    /// Longest name that can be registered, at most 30 characters.
+    #[serde(default = "default_max_name_length")]
+    pub max_name_length: u32,
+    /// Highest referrer rate in basis points, below 10000.
+    #[serde(default = "default_referral_rate_limit")]
+    pub referral_rate_limit: u32,
+}
+
+fn default_max_registration_years() -> u32 {
+    10
+}
+
+fn default_max_name_length() -> u32 {
+    30
+}
+
+fn default_referral_rate_limit() -> u32 {
+    2500
+}
+
+impl RegistryParameters {
+    /// Parameters with the default limits: 10 years, 30 characters and a 25% referral cap.
+    pub fn with_one_year(one_year: u32) -> Self {
+        Self {
+            one_year,
+            max_registration_years: default_max_registration_years(),
+            max_name_length: default_max_name_length(),
+            referral_rate_limit: default_referral_rate_limit(),
+        }
+    }
+
+    pub fn to_word(&self) -> Word {
+        Word::new([
+            Felt::new(self.one_year as u64),
+            Felt::new(self.max_registration_years as u64),
+            Felt::new(self.max_name_length as u64),
+            Felt::new(self.referral_rate_limit as u64),
+        ])
+    }
+
+    /// Reads the parameters stored in the registry parameters slot.
+    pub fn from_word(word: Word) -> Self {
+        Self {
+            one_year: word[0].as_int() as u32,
+            max_registration_years: word[1].as_int() as u32,
+            max_name_length: word[2].as_int() as u32,
+            referral_rate_limit: word[3].as_int() as u32,
+        }
+    }
+}
+
+/// Price of a domain with `letter_count` letters, paid in `token` (hex account ID).
+#[derive(Debug, Deserialize)]
+pub struct PriceConfig {
+    pub letter_count: u8,
+    pub token: String,
+    pub price: u64,
+}
+
+impl DeployConfig {
+    pub fn load(path: &Path) -> anyhow::Result<Self> {
+        let content = fs::read_to_string(path)?;
+        Ok(serde_json::from_str(&content)?)
+    }
+
+    /// Returns the configured prices as `(letter_count, token, price)` entries.
+    pub fn price_entries(&self) -> anyhow::Result<Vec<(u8, AccountId, u64)>> {
+        self.prices
+            .iter()
+            .map(|entry| Ok((entry.letter_count, AccountId::from_hex(&entry.token)?, entry.price)))
+            .collect()
+    }
+}
@@ -1,4 +1,7 @@
+use std::{fmt, str::FromStr};
+
 use miden_crypto::{Felt, Word};
+use serde::{Deserialize, Serialize};
 
 // Helper function to encode a single character to its numeric representation
 pub fn encode_char(chr: char) -> Option<u8> {
@@ -40,22 +43,37 @@ pub fn decode_char(encoded: u8) -> Option<char> {
 // Felts in word must not be reversed in storage
 // So we have to reverse here
 // [P4, P3, P2, P1] -> on MASM [P1, P2, P3, P4]
-pub fn encode_domain(domain: String) -> Word {
-    // Validate length: must be > 0 and <= 20
-    let len = domain.len();
-    assert!(len > 0, "Domain name must have at least 1 character");
-    assert!(len <= 20, "Domain name must be at most 20 characters");
+// Panics on invalid names, parse a DomainName to handle them instead
+pub fn encode_domain(domain: impl AsRef<str>) -> Word {
+    match domain.as_ref().parse::<DomainName>() {
+        Ok(domain) => domain.into(),
+        Err(err) => panic!("{}", err),
+    }
+}
 
+pub fn encode_domain_as_felts(domain: impl AsRef<str>) -> [Felt;4] {
+    let encoded_domain = encode_domain(domain).to_vec();
+
+    [encoded_domain[0], encoded_domain[1], encoded_domain[2], encoded_domain[3]]
+}
+
+// Same as encode_domain without the length checks
+pub fn unsafe_encode_domain(domain: impl AsRef<str>) -> Word {
     // Encode each character and store in a vector
     let mut encoded_chars: Vec<u8> = Vec::new();
-    for c in domain.chars() {
+    for c in domain.as_ref().chars() {
         let char_code = encode_char(c)
             .expect(&format!("Invalid character '{}' in domain name", c));
         encoded_chars.push(char_code);
     }
 
+    unsafe_encode_domain_codes(&encoded_chars)
+}
+
+// Packs raw character codes without checking them, used to build malformed domains
+pub fn unsafe_encode_domain_codes(encoded_chars: &[u8]) -> Word {
     // Pack characters into Felts (7 characters per Felt, 8 bits each)
-    // First 7 characters go into felt3, next 7 into felt2, next 6 into felt1
+    // First 7 characters go into felt3, next 7 into felt2, last 7 into felt1
     let mut felt1: u64 = 0;
     let mut felt2: u64 = 0;
     let mut felt3: u64 = 0;
@@ -81,92 +99,236 @@ pub fn encode_domain(domain: String) -> Word {
         Felt::new(felt1),
         Felt::new(felt2),
         Felt::new(felt3),
-        Felt::new(len as u64),
+        Felt::new(encoded_chars.len() as u64),
     ])
 }
 
-pub fn encode_domain_as_felts(domain: String) -> [Felt;4] {
-    let encoded_domain = encode_domain(domain).to_vec();
+// Panics on non-canonical words, use DomainName::try_from to handle them instead
+pub fn decode_domain(encoded_domain: Word) -> String {
+    match DomainName::try_from(encoded_domain) {
+        Ok(domain) => domain.to_string(),
+        Err(err) => panic!("{}", err),
+    }
+}
 
-    [encoded_domain[0], encoded_domain[1], encoded_domain[2], encoded_domain[3]]
+// v2 encoding, 6 bits per character and 10 characters per felt
+// The length felt carries the version: DOMAIN_VERSION_V2 * DOMAIN_VERSION_BASE + length
+// The contract only accepts v2 for names longer than MAX_V1_DOMAIN_LENGTH,
+// use encode_domain to pick the canonical version
+pub fn encode_domain_v2(domain: impl AsRef<str>) -> Word {
+    match domain.as_ref().parse::<DomainName>() {
+        Ok(domain) => unsafe_encode_domain_codes_v2(&domain.char_codes()),
+        Err(err) => panic!("{}", err),
+    }
 }
 
-pub fn unsafe_encode_domain(domain: String) -> Word {
-    // Validate length: must be > 0 and <= 20
-    let len = domain.len();
+// Packs raw character codes in v2 format without checking them
+pub fn unsafe_encode_domain_codes_v2(encoded_chars: &[u8]) -> Word {
+    // First 10 characters go into felt3, next 10 into felt2, last 10 into felt1
+    let mut felts: [u64; 3] = [0; 3];
 
-    // Encode each character and store in a vector
-    let mut encoded_chars: Vec<u8> = Vec::new();
-    for c in domain.chars() {
-        let char_code = encode_char(c)
-            .expect(&format!("Invalid character '{}' in domain name", c));
-        encoded_chars.push(char_code);
+    for (i, &char_code) in encoded_chars.iter().enumerate() {
+        let felt_index = 2 - (i / CHARS_PER_FELT_V2).min(2);
+        felts[felt_index] |= (char_code as u64) << ((i % CHARS_PER_FELT_V2) * BITS_PER_CHAR_V2);
     }
 
-    // Pack characters into Felts (7 characters per Felt, 8 bits each)
-    // First 7 characters go into felt3, next 7 into felt2, next 6 into felt1
-    let mut felt1: u64 = 0;
-    let mut felt2: u64 = 0;
-    let mut felt3: u64 = 0;
+    Word::new([
+        Felt::new(felts[0]),
+        Felt::new(felts[1]),
+        Felt::new(felts[2]),
+        Felt::new(DOMAIN_VERSION_V2 * DOMAIN_VERSION_BASE + encoded_chars.len() as u64),
+    ])
+}
 
-    for (i, &char_code) in encoded_chars.iter().enumerate() {
-        let bit_shift = (i % 7) * 8;
+// Panics on words that are not canonical v2 encodings
+pub fn decode_domain_v2(encoded_domain: Word) -> String {
+    assert_eq!(
+        encoded_domain[3].as_int() / DOMAIN_VERSION_BASE,
+        DOMAIN_VERSION_V2,
+        "Domain word is not v2 encoded"
+    );
 
-        if i < 7 {
-            // First 7 characters go into felt3
-            felt3 |= (char_code as u64) << bit_shift;
-        } else if i < 14 {
-            // Next 7 characters go into felt2
-            felt2 |= (char_code as u64) << bit_shift;
-        } else {
-            // Remaining characters go into felt1
-            felt1 |= (char_code as u64) << bit_shift;
+    decode_domain(encoded_domain)
+}
+
+pub const MAX_DOMAIN_LENGTH: usize = 30;
+pub const MAX_V1_DOMAIN_LENGTH: usize = 21;
+pub const DOMAIN_VERSION_BASE: u64 = 256;
+pub const DOMAIN_VERSION_V1: u64 = 0;
+pub const DOMAIN_VERSION_V2: u64 = 1;
+const CHARS_PER_FELT: usize = 7;
+const BITS_PER_CHAR: usize = 8;
+const CHARS_PER_FELT_V2: usize = 10;
+const BITS_PER_CHAR_V2: usize = 6;
+
+// Reads the character codes of a word, checking they fill the felts in order
+fn decode_char_codes(
+    encoded_domain: Word,
+    length: usize,
+    chars_per_felt: usize,
+    bits_per_char: usize,
+) -> Result<String, DomainError> {
+    let mask = (1u64 << bits_per_char) - 1;
+
+    // Characters fill felt3 first, then felt2, then felt1, from the low bits up
+    let mut char_codes: Vec<u8> = Vec::with_capacity(3 * chars_per_felt);
+    for felt in [encoded_domain[2], encoded_domain[1], encoded_domain[0]] {
+        let mut value = felt.as_int();
+        for _ in 0..chars_per_felt {
+            char_codes.push((value & mask) as u8);
+            value >>= bits_per_char;
+        }
+        if value != 0 {
+            return Err(DomainError::NonCanonicalWord);
         }
     }
 
-    // Format: [felt1, felt2, felt3, length]
-    // This is reversed for MASM storage (becomes [length, felt3, felt2, felt1] on stack)
-    Word::new([
-        Felt::new(felt1),
-        Felt::new(felt2),
-        Felt::new(felt3),
-        Felt::new(len as u64),
-    ])
+    if char_codes[length..].iter().any(|&code| code != 0) {
+        return Err(DomainError::NonCanonicalWord);
+    }
+
+    char_codes[..length]
+        .iter()
+        .map(|&code| decode_char(code).ok_or(DomainError::NonCanonicalWord))
+        .collect()
 }
 
-pub fn decode_domain(encoded_domain: Word) -> String {
-    let felts = encoded_domain.to_vec();
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DomainError {
+    Empty,
+    TooLong(usize),
+    InvalidChar { position: usize, chr: char },
+    NonCanonicalWord,
+}
 
-    // Extract length from the 4th felt
-    let length = felts[3].as_int() as usize;
+impl fmt::Display for DomainError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DomainError::Empty => write!(f, "Domain name must have at least 1 character"),
+            DomainError::TooLong(len) => write!(
+                f,
+                "Domain name must be at most {} characters, got {}",
+                MAX_DOMAIN_LENGTH, len
+            ),
+            DomainError::InvalidChar { position, chr } => {
+                write!(f, "Invalid character '{}' at position {} in domain name", chr, position)
+            }
+            DomainError::NonCanonicalWord => write!(f, "Word is not a canonical domain encoding"),
+        }
+    }
+}
 
-    // Extract the three data felts
-    let felt1 = felts[0].as_int();
-    let felt2 = felts[1].as_int();
-    let felt3 = felts[2].as_int();
+impl std::error::Error for DomainError {}
 
-    let mut decoded_chars: Vec<char> = Vec::new();
+// Validated, lowercase domain name (a-z, 0-9, 1 to 30 characters, v2 encoded above 21)
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct DomainName(String);
 
-    // Decode characters from each felt (7 characters per felt, 8 bits each)
-    for i in 0..length {
-        let char_code = if i < 7 {
-            // First 7 characters from felt3
-            ((felt3 >> (i * 8)) & 0xFF) as u8
-        } else if i < 14 {
-            // Next 7 characters from felt2
-            ((felt2 >> ((i - 7) * 8)) & 0xFF) as u8
-        } else {
-            // Remaining characters from felt1
-            ((felt1 >> ((i - 14) * 8)) & 0xFF) as u8
-        };
+impl DomainName {
+    // Version the contract expects for this name
+    pub fn version(&self) -> u64 {
+        if self.len() > MAX_V1_DOMAIN_LENGTH { DOMAIN_VERSION_V2 } else { DOMAIN_VERSION_V1 }
+    }
 
-        if let Some(chr) = decode_char(char_code) {
-            decoded_chars.push(chr);
-        } else {
-            panic!("Invalid character code {} at position {}", char_code, i);
+    fn char_codes(&self) -> Vec<u8> {
+        self.0.chars().filter_map(encode_char).collect()
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+}
+
+impl FromStr for DomainName {
+    type Err = DomainError;
+
+    fn from_str(domain: &str) -> Result<Self, Self::Err> {
+        let domain = domain.to_ascii_lowercase();
+
+        if domain.is_empty() {
+            return Err(DomainError::Empty);
         }
+
+        if let Some((position, chr)) = domain.chars().enumerate().find(|(_, chr)| encode_char(*chr).is_none()) {
+            return Err(DomainError::InvalidChar { position, chr });
+        }
+
+        // All characters are ASCII from here, so bytes and characters match
+        if domain.len() > MAX_DOMAIN_LENGTH {
+            return Err(DomainError::TooLong(domain.len()));
+        }
+
+        Ok(DomainName(domain))
     }
+}
+
+impl TryFrom<String> for DomainName {
+    type Error = DomainError;
 
-    decoded_chars.into_iter().collect()
+    fn try_from(domain: String) -> Result<Self, Self::Error> {
+        domain.parse()
+    }
 }
 
+impl From<DomainName> for String {
+    fn from(domain: DomainName) -> Self {
+        domain.0
+    }
+}
+
+impl fmt::Display for DomainName {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl From<&DomainName> for Word {
+    fn from(domain: &DomainName) -> Self {
+        match domain.version() {
+            DOMAIN_VERSION_V1 => unsafe_encode_domain_codes(&domain.char_codes()),
+            _ => unsafe_encode_domain_codes_v2(&domain.char_codes()),
+        }
+    }
+}
+
+impl From<DomainName> for Word {
+    fn from(domain: DomainName) -> Self {
+        Word::from(&domain)
+    }
+}
+
+// Accepts only the encoding produced by encode_domain, same rules as the contract
+impl TryFrom<Word> for DomainName {
+    type Error = DomainError;
+
+    fn try_from(encoded_domain: Word) -> Result<Self, Self::Error> {
+        let version = encoded_domain[3].as_int() / DOMAIN_VERSION_BASE;
+        let length = (encoded_domain[3].as_int() % DOMAIN_VERSION_BASE) as usize;
+        if length == 0 {
+            return Err(DomainError::Empty);
+        }
+
+        // Each name has exactly one valid encoding: v1 up to 21 characters, v2 above
+        let domain = match version {
+            DOMAIN_VERSION_V1 if length <= MAX_V1_DOMAIN_LENGTH => {
+                decode_char_codes(encoded_domain, length, CHARS_PER_FELT, BITS_PER_CHAR)?
+            }
+            DOMAIN_VERSION_V2 if length > MAX_DOMAIN_LENGTH => return Err(DomainError::TooLong(length)),
+            DOMAIN_VERSION_V2 if length > MAX_V1_DOMAIN_LENGTH => {
+                decode_char_codes(encoded_domain, length, CHARS_PER_FELT_V2, BITS_PER_CHAR_V2)?
+            }
+            _ => return Err(DomainError::NonCanonicalWord),
+        };
+
+        Ok(DomainName(domain))
+    }
+}
@@ -4,4 +4,6 @@ pub mod accounts;
 pub mod client;
 pub mod transaction;
 pub mod scripts;
-pub mod notes;
\ No newline at end of file
+pub mod notes;
+pub mod config;
+pub mod quote;
\ No newline at end of file
@@ -1,5 +1,6 @@
 use clap::{Parser, Subcommand};
-use midenname_contracts::{scripts::deploy};
+use std::path::Path;
+use midenname_contracts::{domain::DomainName, scripts::deploy};
 
 #[derive(Parser)]
 #[command(name = "midenname-contracts")]
@@ -12,7 +13,11 @@ struct Cli {
 #[derive(Subcommand)]
 enum Commands {
     /// Deploy the naming contract to the network
-    Deploy,
+    Deploy {
+        /// Path to the deploy config with registry prices
+        #[arg(long, default_value = "./config/deploy.testnet.json")]
+        config: String,
+    },
 
     /// Initialize the deployed registry with owner and payment token
     Init {
@@ -25,7 +30,7 @@ enum Commands {
     Register {
         /// Name to register
         #[arg(long)]
-        name: String,
+        name: DomainName,
 
         /// Account ID to map the name to
         #[arg(long)]
@@ -38,9 +43,9 @@ async fn main() -> anyhow::Result<()> {
     let cli = Cli::parse();
 
     match cli.command {
-        Commands::Deploy => {
+        Commands::Deploy { config } => {
             println!("Deploying Miden Name Registry contract...\n");
-            deploy().await?;
+            deploy(Path::new(&config)).await?;
         }
         Commands::Init { owner } => {
             println!("Initializing registry...");
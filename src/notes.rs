@@ -6,15 +6,18 @@ use miden_client::{
     ScriptBuilder,
     account::AccountId,
     note::{
-        Note, NoteAssets, NoteExecutionHint, NoteExecutionMode, NoteInputs, NoteMetadata,
+        Note, NoteAssets, NoteExecutionHint, NoteInputs, NoteMetadata,
         NoteRecipient, NoteTag, NoteType,
     },
     transaction::TransactionKernel,
 };
-use miden_crypto::{Felt, Word};
+use miden_crypto::{Felt, Word, hash::rpo::Rpo256};
+use miden_lib::note::WellKnownNote;
 use rand::Rng;
 use std::{fs, path::Path, sync::Arc};
 
+use crate::{config::RegistryParameters, storage::commitment_ages};
+
 pub async fn create_note_for_naming(
     name: String,
     inputs: NoteInputs,
@@ -34,7 +37,7 @@ pub async fn create_note_for_naming(
         .unwrap();
 
     let recipient = NoteRecipient::new(serial, note_script, inputs.clone());
-    let tag = NoteTag::for_public_use_case(0, 0, NoteExecutionMode::Local).unwrap();
+    let tag = NoteTag::from_account_id(target_id);
     let metadata = NoteMetadata::new(
         sender,
         NoteType::Public,
@@ -46,6 +49,656 @@ pub async fn create_note_for_naming(
     Ok(note)
 }
 
+/// Creates a note registering `domain` for `reg_len` years, paid with `assets`.
+/// The registry only accepts it while its min commitment age is zero, otherwise
+/// use [create_register_with_commitment_note].
+/// The domain goes to `owner` when it is set and to the sender otherwise.
+/// Change and assets other than `token` are refunded to the sender as a P2ID note
+/// built with `refund_serial_num`.
+#[allow(clippy::too_many_arguments)]
+pub async fn create_register_note(
+    sender: AccountId,
+    naming_id: AccountId,
+    token: AccountId,
+    domain: Word,
+    reg_len: u64,
+    owner: Option<AccountId>,
+    assets: NoteAssets,
+    refund_serial_num: Word,
+) -> anyhow::Result<Note> {
+    let mut inputs = build_registration_inputs(token, domain, reg_len);
+    inputs.extend(build_owner_inputs(owner));
+    inputs.extend(build_refund_note_inputs(sender, refund_serial_num));
+
+    create_note_for_naming("register_name".to_string(), NoteInputs::new(inputs)?, sender, naming_id, assets).await
+}
+
+/// Same as [create_register_note], crediting `referrer` with its referral fee.
+#[allow(clippy::too_many_arguments)]
+pub async fn create_register_with_referrer_note(
+    sender: AccountId,
+    naming_id: AccountId,
+    referrer: AccountId,
+    token: AccountId,
+    domain: Word,
+    reg_len: u64,
+    owner: Option<AccountId>,
+    assets: NoteAssets,
+    refund_serial_num: Word,
+) -> anyhow::Result<Note> {
+    let mut inputs = vec![referrer.suffix(), referrer.prefix().as_felt(), Felt::new(0), Felt::new(0)];
+    inputs.extend(build_registration_inputs(token, domain, reg_len));
+    inputs.extend(build_owner_inputs(owner));
+    inputs.extend(build_refund_note_inputs(sender, refund_serial_num));
+
+    create_note_for_naming("register_with_referrer".to_string(), NoteInputs::new(inputs)?, sender, naming_id, assets).await
+}
+
+/// Creates a note storing a registration `commitment` on the registry, built with
+/// [compute_registration_commitment]. The domain stays hidden until it is revealed with
+/// [create_register_with_commitment_note].
+pub async fn create_commit_note(
+    sender: AccountId,
+    naming_id: AccountId,
+    commitment: Word,
+) -> anyhow::Result<Note> {
+    create_note_for_naming(
+        "commit_registration".to_string(),
+        NoteInputs::new(commitment.to_vec())?,
+        sender,
+        naming_id,
+        NoteAssets::new(vec![])?,
+    )
+    .await
+}
+
+/// Creates a note pointing `domain` at the `target` account. The target only reverse resolves to
+/// `domain` after it sends a [create_set_primary_name_note] itself.
+pub async fn create_set_resolution_target_note(
+    owner: AccountId,
+    naming_id: AccountId,
+    domain: Word,
+    target: AccountId,
+) -> anyhow::Result<Note> {
+    let mut inputs = domain.to_vec();
+    inputs.extend([target.suffix(), target.prefix().as_felt(), Felt::new(0), Felt::new(0)]);
+
+    create_note_for_naming(
+        "set_resolution_target".to_string(),
+        NoteInputs::new(inputs)?,
+        owner,
+        naming_id,
+        NoteAssets::new(vec![])?,
+    )
+    .await
+}
+
+/// Creates a note unlinking `domain` from the account it was activated for. The owner keeps the
+/// domain until it expires.
+pub async fn create_deactivate_domain_note(
+    owner: AccountId,
+    naming_id: AccountId,
+    domain: Word,
+) -> anyhow::Result<Note> {
+    create_note_for_naming(
+        "deactivate_domain".to_string(),
+        NoteInputs::new(domain.to_vec())?,
+        owner,
+        naming_id,
+        NoteAssets::new(vec![])?,
+    )
+    .await
+}
+
+/// Creates a note making `domain` the primary name of the sender, the name its account reverse
+/// resolves to. `domain` must already be activated for the sender.
+pub async fn create_set_primary_name_note(
+    sender: AccountId,
+    naming_id: AccountId,
+    domain: Word,
+) -> anyhow::Result<Note> {
+    create_note_for_naming(
+        "set_primary_name".to_string(),
+        NoteInputs::new(domain.to_vec())?,
+        sender,
+        naming_id,
+        NoteAssets::new(vec![])?,
+    )
+    .await
+}
+
+/// Same as [create_register_note], revealing `domain` and the `secret` of a commitment the
+/// sender stored earlier with [create_commit_note]. `referrer` is credited with its referral
+/// fee when it is set.
+#[allow(clippy::too_many_arguments)]
+pub async fn create_register_with_commitment_note(
+    sender: AccountId,
+    naming_id: AccountId,
+    token: AccountId,
+    domain: Word,
+    reg_len: u64,
+    owner: Option<AccountId>,
+    referrer: Option<AccountId>,
+    secret: Word,
+    assets: NoteAssets,
+    refund_serial_num: Word,
+) -> anyhow::Result<Note> {
+    let mut registration_inputs = build_registration_inputs(token, domain, reg_len);
+    // The referrer and owner go in the unused felts of the TOKEN and REG_LEN words
+    if let Some(referrer) = referrer {
+        registration_inputs[2] = referrer.suffix();
+        registration_inputs[3] = referrer.prefix().as_felt();
+    }
+    if let Some(owner) = owner {
+        registration_inputs[10] = owner.suffix();
+        registration_inputs[11] = owner.prefix().as_felt();
+    }
+    let mut inputs = secret.to_vec();
+    inputs.extend(registration_inputs);
+    inputs.extend(build_refund_note_inputs(sender, refund_serial_num));
+
+    create_note_for_naming("register_with_commitment".to_string(), NoteInputs::new(inputs)?, sender, naming_id, assets).await
+}
+
+/// Creates a note extending `domain` by `reg_len` years, paid with `assets`.
+/// Change is refunded like in [create_register_note].
+pub async fn create_extend_domain_note(
+    sender: AccountId,
+    naming_id: AccountId,
+    token: AccountId,
+    domain: Word,
+    reg_len: u64,
+    assets: NoteAssets,
+    refund_serial_num: Word,
+) -> anyhow::Result<Note> {
+    let mut inputs = build_registration_inputs(token, domain, reg_len);
+    inputs.extend(build_refund_note_inputs(sender, refund_serial_num));
+
+    create_note_for_naming("extend_domain".to_string(), NoteInputs::new(inputs)?, sender, naming_id, assets).await
+}
+
+/// Creates a note asking the registry to pay out `amount` of the sender's referral
+/// earnings in `token`. The registry sends them back to the referrer as a P2ID note
+/// built with `payout_serial_num`.
+pub async fn create_claim_referral_earnings_note(
+    referrer: AccountId,
+    naming_id: AccountId,
+    token: AccountId,
+    amount: u64,
+    payout_serial_num: Word,
+) -> anyhow::Result<Note> {
+    let mut inputs = build_payout_note_inputs(referrer, payout_serial_num)?;
+    inputs.extend([Felt::new(amount), Felt::new(0), Felt::new(0), Felt::new(0)]);
+    inputs.extend([token.suffix(), token.prefix().as_felt(), Felt::new(0), Felt::new(0)]);
+
+    create_note_for_naming(
+        "claim_referral_earnings".to_string(),
+        NoteInputs::new(inputs)?,
+        referrer,
+        naming_id,
+        NoteAssets::new(vec![])?,
+    )
+    .await
+}
+
+/// Creates a note asking the registry to pay out all claimable protocol revenue in
+/// `token` to the registry owner as a P2ID note built with `payout_serial_num`.
+pub async fn create_claim_protocol_revenue_note(
+    owner: AccountId,
+    naming_id: AccountId,
+    token: AccountId,
+    payout_serial_num: Word,
+) -> anyhow::Result<Note> {
+    let mut inputs = build_payout_note_inputs(owner, payout_serial_num)?;
+    inputs.extend([token.suffix(), token.prefix().as_felt(), Felt::new(0), Felt::new(0)]);
+
+    create_note_for_naming(
+        "claim_protocol_revenue".to_string(),
+        NoteInputs::new(inputs)?,
+        owner,
+        naming_id,
+        NoteAssets::new(vec![])?,
+    )
+    .await
+}
+
+/// Creates a note asking the registry to send `amount` of `token` that is not owed as
+/// protocol or referral revenue to the registry owner as a P2ID note built with
+/// `payout_serial_num`.
+pub async fn create_withdraw_assets_note(
+    owner: AccountId,
+    naming_id: AccountId,
+    token: AccountId,
+    amount: u64,
+    payout_serial_num: Word,
+) -> anyhow::Result<Note> {
+    let mut inputs = build_payout_note_inputs(owner, payout_serial_num)?;
+    inputs.extend([Felt::new(amount), Felt::new(0), Felt::new(0), Felt::new(0)]);
+    inputs.extend([token.suffix(), token.prefix().as_felt(), Felt::new(0), Felt::new(0)]);
+
+    create_note_for_naming(
+        "withdraw_assets".to_string(),
+        NoteInputs::new(inputs)?,
+        owner,
+        naming_id,
+        NoteAssets::new(vec![])?,
+    )
+    .await
+}
+
+/// Creates a note asking the registry to set the price of every `(letter_count, token, price)`
+/// entry in `prices`. Names longer than the 5 letter breakpoint are charged the breakpoint price.
+pub async fn create_set_prices_note(
+    owner: AccountId,
+    naming_id: AccountId,
+    prices: &[(u8, AccountId, u64)],
+) -> anyhow::Result<Note> {
+    create_note_for_naming(
+        "set_prices".to_string(),
+        NoteInputs::new(build_set_prices_inputs(prices))?,
+        owner,
+        naming_id,
+        NoteAssets::new(vec![])?,
+    )
+    .await
+}
+
+/// Creates a note asking the registry to set the grace period, the seconds after expiry
+/// during which a domain can still be renewed for its previous owner but not registered by anyone else.
+pub async fn create_set_grace_period_note(
+    owner: AccountId,
+    naming_id: AccountId,
+    grace_period: u32,
+) -> anyhow::Result<Note> {
+    let inputs = vec![Felt::new(grace_period as u64), Felt::new(0), Felt::new(0), Felt::new(0)];
+
+    create_note_for_naming(
+        "set_grace_period".to_string(),
+        NoteInputs::new(inputs)?,
+        owner,
+        naming_id,
+        NoteAssets::new(vec![])?,
+    )
+    .await
+}
+
+/// Creates a note asking the registry to charge `start_premium` of `token` on top of the price
+/// of a domain right after its grace period ends, decaying to zero over `decay_window` seconds.
+/// The decay window is shared by all tokens.
+pub async fn create_set_premium_note(
+    owner: AccountId,
+    naming_id: AccountId,
+    token: AccountId,
+    start_premium: u32,
+    decay_window: u32,
+) -> anyhow::Result<Note> {
+    let inputs = vec![
+        Felt::new(decay_window as u64),
+        Felt::new(0),
+        Felt::new(0),
+        Felt::new(0),
+        token.suffix(),
+        token.prefix().as_felt(),
+        Felt::new(0),
+        Felt::new(0),
+        Felt::new(start_premium as u64),
+        Felt::new(0),
+        Felt::new(0),
+        Felt::new(0),
+    ];
+
+    create_note_for_naming(
+        "set_premium".to_string(),
+        NoteInputs::new(inputs)?,
+        owner,
+        naming_id,
+        NoteAssets::new(vec![])?,
+    )
+    .await
+}
+
+/// Creates a note asking the registry to take `discount_bps` basis points off registrations
+/// and renewals of at least `min_years` years. A zero discount removes the tier.
+pub async fn create_set_discount_tier_note(
+    owner: AccountId,
+    naming_id: AccountId,
+    min_years: u64,
+    discount_bps: u64,
+) -> anyhow::Result<Note> {
+    let inputs = vec![
+        Felt::new(min_years),
+        Felt::new(0),
+        Felt::new(0),
+        Felt::new(0),
+        Felt::new(discount_bps),
+        Felt::new(0),
+        Felt::new(0),
+        Felt::new(0),
+    ];
+
+    create_note_for_naming(
+        "set_discount_tier".to_string(),
+        NoteInputs::new(inputs)?,
+        owner,
+        naming_id,
+        NoteAssets::new(vec![])?,
+    )
+    .await
+}
+
+/// Creates a note asking the registry to replace its limits with `parameters`. The max name length
+/// can not decrease and max years times one year can not shrink, so registered names stay renewable.
+pub async fn create_set_parameters_note(
+    owner: AccountId,
+    naming_id: AccountId,
+    parameters: RegistryParameters,
+) -> anyhow::Result<Note> {
+    create_note_for_naming(
+        "set_parameters".to_string(),
+        NoteInputs::new(parameters.to_word().to_vec())?,
+        owner,
+        naming_id,
+        NoteAssets::new(vec![])?,
+    )
+    .await
+}
+
+/// Creates a note marking `key` as auction-only, or a regular domain again when `auction_only`
+/// is false. `key` is a domain word or an [auction_length_key].
+pub async fn create_set_auction_only_note(
+    owner: AccountId,
+    naming_id: AccountId,
+    key: Word,
+    auction_only: bool,
+) -> anyhow::Result<Note> {
+    let mut inputs = key.to_vec();
+    inputs.extend([Felt::new(auction_only as u64), Felt::new(0), Felt::new(0), Felt::new(0)]);
+
+    create_note_for_naming(
+        "set_auction_only".to_string(),
+        NoteInputs::new(inputs)?,
+        owner,
+        naming_id,
+        NoteAssets::new(vec![])?,
+    )
+    .await
+}
+
+/// Creates a note setting how old a registration commitment must be before it can be used
+/// and after how many seconds it expires.
+pub async fn create_set_commitment_ages_note(
+    owner: AccountId,
+    naming_id: AccountId,
+    min_age: u32,
+    max_age: u32,
+) -> anyhow::Result<Note> {
+    create_note_for_naming(
+        "set_commitment_ages".to_string(),
+        NoteInputs::new(commitment_ages(min_age, max_age).to_vec())?,
+        owner,
+        naming_id,
+        NoteAssets::new(vec![])?,
+    )
+    .await
+}
+
+/// Creates a note setting how many seconds the bidding and reveal phases of an auction last.
+pub async fn create_set_auction_periods_note(
+    owner: AccountId,
+    naming_id: AccountId,
+    bidding_period: u32,
+    reveal_period: u32,
+) -> anyhow::Result<Note> {
+    let inputs = vec![Felt::new(bidding_period as u64), Felt::new(reveal_period as u64), Felt::new(0), Felt::new(0)];
+
+    create_note_for_naming(
+        "set_auction_periods".to_string(),
+        NoteInputs::new(inputs)?,
+        owner,
+        naming_id,
+        NoteAssets::new(vec![])?,
+    )
+    .await
+}
+
+/// Creates a sealed bid on the auction-only `domain`, locking the `token` part of `assets` as
+/// deposit. `commitment` comes from [compute_bid_commitment], so the deposit should not tell
+/// the bid. Other assets are refunded like in [create_register_note].
+#[allow(clippy::too_many_arguments)]
+pub async fn create_bid_note(
+    sender: AccountId,
+    naming_id: AccountId,
+    token: AccountId,
+    domain: Word,
+    commitment: Word,
+    assets: NoteAssets,
+    refund_serial_num: Word,
+) -> anyhow::Result<Note> {
+    let mut inputs = commitment.to_vec();
+    inputs.extend(domain.to_vec());
+    inputs.extend([token.suffix(), token.prefix().as_felt(), Felt::new(0), Felt::new(0)]);
+    inputs.extend(build_refund_note_inputs(sender, refund_serial_num));
+
+    create_note_for_naming("bid".to_string(), NoteInputs::new(inputs)?, sender, naming_id, assets).await
+}
+
+/// Creates a note revealing the `bid` of a sealed bid on `domain` during the reveal phase.
+/// `auction_nonce` must be the one the bid committed to.
+pub async fn create_reveal_bid_note(
+    sender: AccountId,
+    naming_id: AccountId,
+    domain: Word,
+    bid: u64,
+    auction_nonce: u64,
+    secret: Word,
+) -> anyhow::Result<Note> {
+    create_note_for_naming(
+        "reveal_bid".to_string(),
+        NoteInputs::new(build_bid_inputs(domain, bid, auction_nonce, secret))?,
+        sender,
+        naming_id,
+        NoteAssets::new(vec![])?,
+    )
+    .await
+}
+
+/// Creates a note settling a bid on `domain` once the auction ended. The winner gets the domain,
+/// and the unspent deposit goes back to the sender as a P2ID note built with `refund_serial_num`.
+pub async fn create_settle_bid_note(
+    sender: AccountId,
+    naming_id: AccountId,
+    domain: Word,
+    bid: u64,
+    auction_nonce: u64,
+    secret: Word,
+    refund_serial_num: Word,
+) -> anyhow::Result<Note> {
+    let mut inputs = build_bid_inputs(domain, bid, auction_nonce, secret);
+    inputs.extend(build_refund_note_inputs(sender, refund_serial_num));
+
+    create_note_for_naming("settle_bid".to_string(), NoteInputs::new(inputs)?, sender, naming_id, NoteAssets::new(vec![])?).await
+}
+
+/// Creates a pay-to-name note. Only the account `domain` resolves to on the registry
+/// can consume it, and only while the domain is not expired.
+pub async fn create_p2n_note(
+    sender: AccountId,
+    registry_id: AccountId,
+    domain: Word,
+    assets: NoteAssets,
+) -> anyhow::Result<Note> {
+    let inputs = vec![
+        domain[0],
+        domain[1],
+        domain[2],
+        domain[3],
+        registry_id.suffix(),
+        registry_id.prefix().as_felt(),
+    ];
+
+    create_note_for_naming("P2N".to_string(), NoteInputs::new(inputs)?, sender, registry_id, assets).await
+}
+
+/// Same as [create_p2n_note], but `sender` can reclaim the assets from block
+/// `reclaim_block_height` onwards, without checking the registry.
+pub async fn create_p2ne_note(
+    sender: AccountId,
+    registry_id: AccountId,
+    domain: Word,
+    assets: NoteAssets,
+    reclaim_block_height: u32,
+) -> anyhow::Result<Note> {
+    let inputs = vec![
+        domain[0],
+        domain[1],
+        domain[2],
+        domain[3],
+        registry_id.suffix(),
+        registry_id.prefix().as_felt(),
+        Felt::new(reclaim_block_height as u64),
+    ];
+
+    create_note_for_naming("P2NE".to_string(), NoteInputs::new(inputs)?, sender, registry_id, assets).await
+}
+
+/// Builds the `[OWNER, PARAMETERS, P2ID_SCRIPT_ROOT]` inputs of the `initialize_naming` note.
+pub fn build_initialize_inputs(owner: AccountId, parameters: RegistryParameters) -> Vec<Felt> {
+    let mut inputs = vec![owner.suffix(), owner.prefix().as_felt(), Felt::new(0), Felt::new(0)];
+    inputs.extend(parameters.to_word().to_vec());
+    inputs.extend(WellKnownNote::P2ID.script_root().to_vec());
+    inputs
+}
+
+/// Builds one `[token_suffix, token_prefix, letter_count, price]` input word per price entry.
+pub fn build_set_prices_inputs(prices: &[(u8, AccountId, u64)]) -> Vec<Felt> {
+    prices
+        .iter()
+        .flat_map(|(letter_count, token, price)| {
+            [
+                token.suffix(),
+                token.prefix().as_felt(),
+                Felt::new(*letter_count as u64),
+                Felt::new(*price),
+            ]
+        })
+        .collect()
+}
+
+/// Builds the `[TOKEN, DOMAIN, REG_LEN]` note inputs shared by registration and renewal notes.
+pub fn build_registration_inputs(token: AccountId, domain: Word, reg_len: u64) -> Vec<Felt> {
+    vec![
+        token.suffix(),
+        token.prefix().as_felt(),
+        Felt::new(0),
+        Felt::new(0),
+        domain[0],
+        domain[1],
+        domain[2],
+        domain[3],
+        Felt::new(reg_len),
+        Felt::new(0),
+        Felt::new(0),
+        Felt::new(0),
+    ]
+}
+
+/// Builds the optional `OWNER` word of register notes, all zeros registers for the sender.
+pub fn build_owner_inputs(owner: Option<AccountId>) -> Vec<Felt> {
+    match owner {
+        Some(owner) => vec![owner.suffix(), owner.prefix().as_felt(), Felt::new(0), Felt::new(0)],
+        None => vec![Felt::new(0); 4],
+    }
+}
+
+/// Builds the trailing `[REFUND_SERIAL_NUM, NOTE_DETAILS]` note inputs the registry reads
+/// to refund change to `sender` as a public P2ID note.
+pub fn build_refund_note_inputs(sender: AccountId, refund_serial_num: Word) -> Vec<Felt> {
+    let tag = NoteTag::from_account_id(sender);
+
+    vec![
+        refund_serial_num[0],
+        refund_serial_num[1],
+        refund_serial_num[2],
+        refund_serial_num[3],
+        Felt::new(NoteExecutionHint::always().into()),
+        NoteType::Public.into(),
+        Felt::new(0), // aux
+        tag.into(),
+    ]
+}
+
+/// Builds the recipient of a P2ID note paying out to `target`.
+pub fn build_p2id_recipient(target: AccountId, serial_num: Word) -> anyhow::Result<NoteRecipient> {
+    let note_script = WellKnownNote::P2ID.script();
+    let note_inputs = NoteInputs::new(vec![target.suffix(), target.prefix().as_felt()])?;
+
+    Ok(NoteRecipient::new(serial_num, note_script, note_inputs))
+}
+
+/// Builds the `[RECIPIENT, NOTE_DETAILS]` note inputs the registry uses to create
+/// a public P2ID payout note to `target`.
+pub fn build_payout_note_inputs(target: AccountId, serial_num: Word) -> anyhow::Result<Vec<Felt>> {
+    let recipient = build_p2id_recipient(target, serial_num)?.digest();
+    let tag = NoteTag::from_account_id(target);
+
+    Ok(vec![
+        recipient[0],
+        recipient[1],
+        recipient[2],
+        recipient[3],
+        Felt::new(NoteExecutionHint::always().into()),
+        NoteType::Public.into(),
+        Felt::new(0), // aux
+        tag.into(),
+    ])
+}
+
+/// Computes the commitment `hash(hash(DOMAIN, SENDER), SECRET)` the registry expects
+/// before `sender` can register `domain` through a commitment.
+pub fn compute_registration_commitment(domain: Word, sender: AccountId, secret: Word) -> Word {
+    let sender_word = Word::new([sender.suffix(), sender.prefix().as_felt(), Felt::new(0), Felt::new(0)]);
+    let domain_sender_hash = Rpo256::merge(&[domain, sender_word]);
+
+    Rpo256::merge(&[domain_sender_hash, secret])
+}
+
+/// Computes the sealed bid commitment `hash(hash(DOMAIN, BIDDER), hash(BID, SECRET))`.
+/// `auction_nonce` is the number of auctions started for `domain` (slot 27), counting the
+/// one a first bid starts, so the bid can only be revealed in that auction.
+pub fn compute_bid_commitment(domain: Word, bidder: AccountId, bid: u64, auction_nonce: u64, secret: Word) -> Word {
+    let bidder_word = Word::new([bidder.suffix(), bidder.prefix().as_felt(), Felt::new(0), Felt::new(0)]);
+    let bid_word = Word::new([Felt::new(bid), Felt::new(auction_nonce), Felt::new(0), Felt::new(0)]);
+    let domain_bidder_hash = Rpo256::merge(&[domain, bidder_word]);
+    let bid_secret_hash = Rpo256::merge(&[bid_word, secret]);
+
+    Rpo256::merge(&[domain_bidder_hash, bid_secret_hash])
+}
+
+/// Key marking every domain with `letter_count` characters as auction-only.
+pub fn auction_length_key(letter_count: u8) -> Word {
+    Word::new([Felt::new(0), Felt::new(0), Felt::new(0), Felt::new(letter_count as u64)])
+}
+
+/// Builds the `[DOMAIN, BID, SECRET]` note inputs shared by reveal and settle notes.
+pub fn build_bid_inputs(domain: Word, bid: u64, auction_nonce: u64, secret: Word) -> Vec<Felt> {
+    let mut inputs = domain.to_vec();
+    inputs.extend([Felt::new(bid), Felt::new(auction_nonce), Felt::new(0), Felt::new(0)]);
+    inputs.extend(secret.to_vec());
+    inputs
+}
+
+/// Generates a random secret for a registration commitment
+pub fn generate_commitment_secret() -> Word {
+    let mut rng = rand::rng();
+
+    Word::new([
+        Felt::new(rng.random::<u32>() as u64),
+        Felt::new(rng.random::<u32>() as u64),
+        Felt::new(rng.random::<u32>() as u64),
+        Felt::new(rng.random::<u32>() as u64),
+    ])
+}
+
 pub fn create_library(account_code: String, library_path: &str) -> anyhow::Result<Library> {
     let assembler = TransactionKernel::assembler().with_debug_mode(true);
     let source_manager = Arc::new(DefaultSourceManager::default());
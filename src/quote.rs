@@ -0,0 +1,56 @@
+use miden_client::account::{AccountId, AccountStorage};
+use miden_crypto::Word;
+
+use crate::{
+    config::RegistryParameters,
+    storage::{
+        DOMAIN_EXPIRY_DATES_SLOT, GRACE_PERIOD_SLOT, PARAMETERS_SLOT, PREMIUM_DECAY_SLOT, START_PREMIUMS_SLOT, token_key,
+    },
+};
+
+/// Premium of a domain released at `release_time` (expiry plus grace period), seen at `timestamp`.
+/// Decays linearly from `start_premium` to zero over `decay_window` seconds, like `_calculate_premium`.
+pub fn calculate_premium(start_premium: u64, decay_window: u64, release_time: u64, timestamp: u64) -> u64 {
+    if timestamp < release_time {
+        return 0;
+    }
+
+    let elapsed = timestamp - release_time;
+    if elapsed >= decay_window {
+        return 0;
+    }
+
+    start_premium * (decay_window - elapsed) / decay_window
+}
+
+/// Reads the registry storage and returns the premium a registration of `domain` paid in `token`
+/// would pay at block `timestamp`. Zero for domains that were never registered or already cleared.
+pub fn quote_premium(storage: &AccountStorage, domain: Word, token: AccountId, timestamp: u32) -> anyhow::Result<u64> {
+    let expiry = storage.get_map_item(DOMAIN_EXPIRY_DATES_SLOT, domain)?[0].as_int();
+    if expiry == 0 {
+        return Ok(0);
+    }
+
+    let grace_period = storage.get_item(GRACE_PERIOD_SLOT)?[0].as_int();
+    let decay_window = storage.get_item(PREMIUM_DECAY_SLOT)?[0].as_int();
+    let start_premium = storage.get_map_item(START_PREMIUMS_SLOT, token_key(token))?[0].as_int();
+
+    Ok(calculate_premium(start_premium, decay_window, expiry + grace_period, timestamp as u64))
+}
+
+/// Reads the registry storage and returns how many years `domain` can still be extended by at
+/// block `timestamp`. Zero once the domain can no longer be renewed.
+pub fn quote_max_extension(storage: &AccountStorage, domain: Word, timestamp: u32) -> anyhow::Result<u64> {
+    let expiry = storage.get_map_item(DOMAIN_EXPIRY_DATES_SLOT, domain)?[0].as_int();
+    let grace_period = storage.get_item(GRACE_PERIOD_SLOT)?[0].as_int();
+    if expiry + grace_period <= timestamp as u64 {
+        return Ok(0);
+    }
+
+    let parameters = RegistryParameters::from_word(storage.get_item(PARAMETERS_SLOT)?);
+    let one_year = parameters.one_year as u64;
+    let max_years = parameters.max_registration_years as u64;
+    let max_expiry = timestamp as u64 + max_years * one_year;
+
+    Ok((max_expiry.saturating_sub(expiry) / one_year).min(max_years))
+}
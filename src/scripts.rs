@@ -1,20 +1,26 @@
 use miden_client::{
-    account::AccountId,
     note::{NoteAssets, NoteInputs},
     transaction::{OutputNote, TransactionRequestBuilder},
 };
-use miden_crypto::Felt;
+use std::path::Path;
 use tokio::time::{Duration, sleep};
 
 use crate::{
     accounts::{create_deployer_account, create_naming_account},
     client::{create_keystore, initiate_client},
-    notes::create_note_for_naming,
+    config::DeployConfig,
+    notes::{
+        build_initialize_inputs, create_note_for_naming, create_set_grace_period_note,
+        create_set_prices_note,
+    },
     transaction::wait_for_tx,
 };
 
-pub async fn deploy() -> anyhow::Result<()> {
+pub async fn deploy(config_path: &Path) -> anyhow::Result<()> {
     println!("Starting Miden Name Registry deployment...");
+    let config = DeployConfig::load(config_path)?;
+    let prices = config.price_entries()?;
+
     let mut keystore = create_keystore()?;
     let mut client = initiate_client(keystore.clone()).await?;
 
@@ -22,19 +28,10 @@ pub async fn deploy() -> anyhow::Result<()> {
     let naming_account = create_naming_account(&mut client).await?;
     client.sync_state().await?;
 
-    let initialize_inputs = NoteInputs::new(
-        [
-            Felt::new(deployer_account.id().suffix().into()),
-            Felt::new(deployer_account.id().prefix().into()),
-            Felt::new(0),
-            Felt::new(0),
-            Felt::new(5000),
-            Felt::new(0),
-            Felt::new(0),
-            Felt::new(0),
-        ]
-        .to_vec(),
-    )?;
+    let initialize_inputs = NoteInputs::new(build_initialize_inputs(
+        deployer_account.id(),
+        config.parameters,
+    ))?;
     let init_note = create_note_for_naming(
         "initialize_naming".to_string(),
         initialize_inputs,
@@ -66,29 +63,27 @@ pub async fn deploy() -> anyhow::Result<()> {
 
     client.sync_state().await?;
 
-    println!("Setting prices");
+    println!("Setting prices and grace period");
 
-    let payment_token_id = AccountId::from_hex("0x54bf4e12ef20082070758b022456c7")?;
-
-    let set_prices_note_inputs = NoteInputs::new(
-        [
-            Felt::new(payment_token_id.suffix().into()),
-            Felt::new(payment_token_id.prefix().into()),
-        ]
-        .to_vec(),
-    )?;
+    let set_prices_note = create_set_prices_note(
+        deployer_account.id(),
+        naming_account.id(),
+        &prices,
+    )
+    .await?;
 
-    let set_prices_note = create_note_for_naming(
-        "set_all_prices_testnet".to_string(),
-        set_prices_note_inputs,
+    let set_grace_period_note = create_set_grace_period_note(
         deployer_account.id(),
         naming_account.id(),
-        NoteAssets::new(vec![]).unwrap(),
+        config.grace_period,
     )
     .await?;
 
     let set_price_req = TransactionRequestBuilder::new()
-        .own_output_notes(vec![OutputNote::Full(set_prices_note)])
+        .own_output_notes(vec![
+            OutputNote::Full(set_prices_note),
+            OutputNote::Full(set_grace_period_note),
+        ])
         .build()?;
 
     let set_prices_tx_id = client
@@ -101,7 +96,7 @@ pub async fn deploy() -> anyhow::Result<()> {
     );
     client.sync_state().await?;
 
-    println!("set prices tx submitted, waiting for onchain commitment");
+    println!("set prices and grace period tx submitted, waiting for onchain commitment");
 
     wait_for_tx(&mut client, set_prices_tx_id).await?;
 
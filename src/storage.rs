@@ -1,6 +1,37 @@
-use miden_client::account::{StorageMap, StorageSlot};
+use miden_client::account::{AccountId, StorageMap, StorageSlot};
 use miden_crypto::{Felt, Word};
 
+// Slot indices of the naming account, named like the slot constants of naming.masm
+pub const INIT_FLAG_SLOT: u8 = 0;
+pub const OWNER_SLOT: u8 = 1;
+pub const PRICES_SLOT: u8 = 2;
+pub const ACCOUNT_ID_TO_DOMAIN_SLOT: u8 = 3;
+pub const DOMAIN_TO_ACCOUNT_ID_SLOT: u8 = 4;
+pub const DOMAIN_TO_OWNER_SLOT: u8 = 5;
+pub const REF_RATE_SLOT: u8 = 6;
+pub const REF_TOTAL_REVENUE_SLOT: u8 = 7;
+pub const REF_CLAIMED_REVENUE_SLOT: u8 = 8;
+pub const DOMAIN_COUNT_SLOT: u8 = 9;
+pub const TOTAL_REVENUE_SLOT: u8 = 10;
+pub const CLAIMED_REVENUE_SLOT: u8 = 11;
+pub const DOMAIN_EXPIRY_DATES_SLOT: u8 = 12;
+pub const PARAMETERS_SLOT: u8 = 13;
+pub const REF_OUTSTANDING_REVENUE_SLOT: u8 = 14;
+pub const P2ID_SCRIPT_ROOT_SLOT: u8 = 15;
+pub const COMMITMENTS_SLOT: u8 = 16;
+pub const GRACE_PERIOD_SLOT: u8 = 17;
+pub const PREMIUM_DECAY_SLOT: u8 = 18;
+pub const START_PREMIUMS_SLOT: u8 = 19;
+pub const AUCTION_ONLY_SLOT: u8 = 20;
+pub const AUCTION_PERIODS_SLOT: u8 = 21;
+pub const AUCTIONS_SLOT: u8 = 22;
+pub const HIGHEST_BIDS_SLOT: u8 = 23;
+pub const BIDS_SLOT: u8 = 24;
+pub const BID_DEPOSITS_SLOT: u8 = 25;
+pub const DISCOUNT_TIERS_SLOT: u8 = 26;
+pub const AUCTION_NONCES_SLOT: u8 = 27;
+pub const COMMITMENT_AGES_SLOT: u8 = 28;
+
 fn empty_storage_value() -> StorageSlot {
     StorageSlot::Value(Word::new([
         Felt::new(0),
@@ -19,13 +50,74 @@ pub fn naming_storage() -> Vec<StorageSlot> {
         StorageSlot::Map(StorageMap::new()), // domain to account
         StorageSlot::Map(StorageMap::new()), // domain to owner
         StorageSlot::Map(StorageMap::new()), // calculate price root
-        StorageSlot::Map(StorageMap::new()),
-        StorageSlot::Map(StorageMap::new()),
+        StorageSlot::Map(StorageMap::new()), // referrer & token -> total revenue
+        StorageSlot::Map(StorageMap::new()), // referrer & token -> claimed revenue
         empty_storage_value(),
         StorageSlot::Map(StorageMap::new()),
         StorageSlot::Map(StorageMap::new()),
         StorageSlot::Map(StorageMap::new()),
-        empty_storage_value(), // ONE YEAR TIMESTAMP
+        empty_storage_value(), // parameters [ref_rate_limit, max_name_length, max_reg_len, one_year]
+        StorageSlot::Map(StorageMap::new()), // token -> unclaimed referral revenue
+        empty_storage_value(), // P2ID script root for refunds
+        StorageSlot::Map(StorageMap::new()), // registration commitment -> commit timestamp
+        empty_storage_value(), // grace period
+        empty_storage_value(), // premium decay window
+        StorageSlot::Map(StorageMap::new()), // token -> start premium of released domains
+        StorageSlot::Map(StorageMap::new()), // auction-only domains and lengths
+        empty_storage_value(), // auction bidding and reveal periods
+        StorageSlot::Map(StorageMap::new()), // domain -> auction
+        StorageSlot::Map(StorageMap::new()), // domain -> highest revealed bid commitment
+        StorageSlot::Map(StorageMap::new()), // bid commitment -> bid
+        StorageSlot::Map(StorageMap::new()), // token -> locked bid deposits
+        StorageSlot::Map(default_discount_tiers()), // min years -> discount in basis points
+        StorageSlot::Map(StorageMap::new()), // domain -> number of auctions started
+        StorageSlot::Value(commitment_ages(DEFAULT_MIN_COMMITMENT_AGE, DEFAULT_MAX_COMMITMENT_AGE)), // [max_age, min_age] of registration commitments
         ];
     return storage_slots;
-}
\ No newline at end of file
+}
+
+/// Discount tiers the naming account starts with: 30% off from 3 years and 50% off from 5 years.
+fn default_discount_tiers() -> StorageMap {
+    StorageMap::with_entries(
+        DEFAULT_DISCOUNT_TIERS
+            .iter()
+            .map(|&(min_years, discount_bps)| (discount_tier_key(min_years), discount_value(discount_bps))),
+    )
+    .expect("default discount tiers are unique")
+}
+
+pub const DEFAULT_DISCOUNT_TIERS: [(u64, u64); 2] = [(3, 3000), (5, 5000)];
+
+/// Seconds a registration commitment must wait before it can be used.
+pub const DEFAULT_MIN_COMMITMENT_AGE: u32 = 60;
+/// Seconds after which a registration commitment can no longer be used.
+pub const DEFAULT_MAX_COMMITMENT_AGE: u32 = 86400;
+
+/// Value of the commitment ages slot (28), also the input of a set commitment ages note.
+pub fn commitment_ages(min_age: u32, max_age: u32) -> Word {
+    Word::new([Felt::new(min_age as u64), Felt::new(max_age as u64), Felt::new(0), Felt::new(0)])
+}
+
+/// Key of the discount tier map (slot 26) for registrations of at least `min_years` years.
+pub fn discount_tier_key(min_years: u64) -> Word {
+    Word::new([Felt::new(min_years), Felt::new(0), Felt::new(0), Felt::new(0)])
+}
+
+fn discount_value(discount_bps: u64) -> Word {
+    Word::new([Felt::new(discount_bps), Felt::new(0), Felt::new(0), Felt::new(0)])
+}
+
+/// Key of the referral revenue maps (slots 7 and 8), which track revenue per referrer and payment token.
+pub fn referrer_revenue_key(referrer: AccountId, token: AccountId) -> Word {
+    Word::new([
+        token.suffix(),
+        token.prefix().as_felt(),
+        referrer.suffix(),
+        referrer.prefix().as_felt(),
+    ])
+}
+
+/// Key of the maps tracked per payment token, like the start premiums (slot 19).
+pub fn token_key(token: AccountId) -> Word {
+    Word::new([token.suffix(), token.prefix().as_felt(), Felt::new(0), Felt::new(0)])
+}
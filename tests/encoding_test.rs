@@ -1,5 +1,5 @@
 use miden_crypto::{Felt, Word};
-use midenname_contracts::domain::{encode_domain, decode_domain};
+use midenname_contracts::domain::{DomainError, DomainName, encode_domain, decode_domain, decode_domain_v2, encode_domain_v2, unsafe_encode_domain_codes, unsafe_encode_domain_codes_v2};
 
 #[test]
 fn encode_letter() {
@@ -57,4 +57,115 @@ fn decode_multiple_felts() {
     let decoded_domain = decode_domain(encoded_word);
 
     assert_eq!(decoded_domain, "aliceandbobandjoe");
-}
\ No newline at end of file
+}
+
+#[test]
+fn encode_raw_codes() {
+    let encoded = unsafe_encode_domain_codes(&[1, 12, 9, 3, 5, 37]);
+    let felts = encoded.to_vec();
+
+    assert_eq!(felts[2], Felt::new(0x250503090c01)); // alice + out of charset code
+    assert_eq!(felts[3], Felt::new(6));
+    assert_eq!(unsafe_encode_domain_codes(&[1, 12, 9, 3, 5]), encode_domain("alice"));
+}
+
+#[test]
+fn parse_domain_name_normalizes_case() {
+    let domain: DomainName = "Alice42".parse().unwrap();
+
+    assert_eq!(domain.as_str(), "alice42");
+    assert_eq!(domain.to_string(), "alice42");
+    assert_eq!(Word::from(&domain), encode_domain("alice42"));
+}
+
+#[test]
+fn parse_domain_name_errors() {
+    assert_eq!("".parse::<DomainName>(), Err(DomainError::Empty));
+    assert_eq!("a".repeat(31).parse::<DomainName>(), Err(DomainError::TooLong(31)));
+    assert_eq!("ali-ce".parse::<DomainName>(), Err(DomainError::InvalidChar { position: 3, chr: '-' }));
+    assert_eq!("bob.miden".parse::<DomainName>(), Err(DomainError::InvalidChar { position: 3, chr: '.' }));
+    assert_eq!("çok".parse::<DomainName>(), Err(DomainError::InvalidChar { position: 0, chr: 'ç' }));
+}
+
+#[test]
+fn domain_name_word_round_trip() {
+    for name in ["a", "alice", "aliceandbobandjoe", "abcdefghijklmnopqrstu", "z0123456789", "abcdefghijklmnopqrstuv", "abcdefghijklmnopqrstuvwxyz0123"] {
+        let domain: DomainName = name.parse().unwrap();
+        let word: Word = domain.clone().into();
+
+        assert_eq!(DomainName::try_from(word), Ok(domain));
+        assert_eq!(decode_domain(word), name);
+    }
+}
+
+#[test]
+fn domain_name_rejects_non_canonical_words() {
+    let with_length = |word: Word, len: u64| Word::new([word[0], word[1], word[2], Felt::new(len)]);
+
+    assert_eq!(DomainName::try_from(with_length(encode_domain("abc"), 0)), Err(DomainError::Empty));
+    assert_eq!(DomainName::try_from(with_length(encode_domain("abc"), 22)), Err(DomainError::NonCanonicalWord));
+    assert_eq!(DomainName::try_from(with_length(encode_domain("abc"), 4)), Err(DomainError::NonCanonicalWord));
+    assert_eq!(DomainName::try_from(with_length(encode_domain("abc"), 2)), Err(DomainError::NonCanonicalWord));
+    assert_eq!(DomainName::try_from(unsafe_encode_domain_codes(&[37])), Err(DomainError::NonCanonicalWord));
+    assert_eq!(DomainName::try_from(with_length(unsafe_encode_domain_codes(&[1, 0, 2]), 2)), Err(DomainError::NonCanonicalWord));
+    assert_eq!(DomainName::try_from(with_length(unsafe_encode_domain_codes(&[1, 2, 3, 0, 0, 0, 0, 4]), 4)), Err(DomainError::NonCanonicalWord));
+    assert_eq!(
+        DomainName::try_from(Word::new([Felt::new(0), Felt::new(0), Felt::new(0x0101010101010101), Felt::new(8)])),
+        Err(DomainError::NonCanonicalWord)
+    );
+}
+
+#[test]
+fn domain_name_serde_uses_string_form() {
+    let domain: DomainName = "alice".parse().unwrap();
+
+    assert_eq!(serde_json::to_string(&domain).unwrap(), "\"alice\"");
+    assert_eq!(serde_json::from_str::<DomainName>("\"Alice\"").unwrap(), domain);
+    assert!(serde_json::from_str::<DomainName>("\"ali ce\"").is_err());
+}
+
+
+#[test]
+fn encode_v2_packs_six_bits_per_char() {
+    let domain = "abcdefghijklmnopqrstuvwxyz0123";
+    let encoded = encode_domain(domain);
+    let felts = encoded.to_vec();
+
+    // a..j, k..t, u..3 with 6 bits per character
+    let pack = |codes: std::ops::RangeInclusive<u64>| codes.enumerate().fold(0u64, |felt, (i, code)| felt | code << (i * 6));
+    assert_eq!(felts[2], Felt::new(pack(1..=10)));
+    assert_eq!(felts[1], Felt::new(pack(11..=20)));
+    assert_eq!(felts[0], Felt::new(pack(21..=30)));
+    assert_eq!(felts[3], Felt::new(256 + 30));
+    assert_eq!(encoded, encode_domain_v2(domain));
+    assert_eq!(decode_domain_v2(encoded), domain);
+}
+
+#[test]
+fn encode_picks_version_by_length() {
+    let short = "a".repeat(21);
+    let long = "a".repeat(22);
+
+    assert_eq!(encode_domain(&short)[3], Felt::new(21));
+    assert_eq!(encode_domain(&long)[3], Felt::new(256 + 22));
+    assert_eq!(decode_domain(encode_domain(&long)), long);
+}
+
+#[test]
+fn domain_name_rejects_non_canonical_v2_words() {
+    // Names that fit v1 must not be v2 encoded
+    assert_eq!(DomainName::try_from(encode_domain_v2("alice")), Err(DomainError::NonCanonicalWord));
+    assert_eq!(DomainName::try_from(unsafe_encode_domain_codes_v2(&[1; 31])), Err(DomainError::TooLong(31)));
+    let mut bad_char_codes = vec![1u8; 22];
+    bad_char_codes.push(37);
+    assert_eq!(DomainName::try_from(unsafe_encode_domain_codes_v2(&bad_char_codes)), Err(DomainError::NonCanonicalWord));
+
+    // Unknown version
+    let word = encode_domain("a".repeat(22));
+    assert_eq!(DomainName::try_from(Word::new([word[0], word[1], word[2], Felt::new(2 * 256 + 22)])), Err(DomainError::NonCanonicalWord));
+
+    // Bits above the 10th character of a felt
+    let word = encode_domain("a".repeat(25));
+    let overflowing = Word::new([word[0], word[1], Felt::new(word[2].as_int() | 1u64 << 60), word[3]]);
+    assert_eq!(DomainName::try_from(overflowing), Err(DomainError::NonCanonicalWord));
+}
@@ -0,0 +1,222 @@
+mod test_utils;
+
+use miden_client::{account::AccountId, asset::FungibleAsset, note::{Note, NoteAssets, NoteType}};
+use miden_crypto::{Felt, Word};
+use miden_testing::MockChain;
+use midenname_contracts::{domain::encode_domain, notes::{auction_length_key, compute_bid_commitment, create_bid_note, create_register_note, create_reveal_bid_note, create_set_auction_only_note, create_set_auction_periods_note, create_settle_bid_note}};
+use test_utils::{init_naming, TestingContext};
+
+use crate::test_utils::{add_note_to_builder, advance_to_timestamp, create_p2id_note_exact, execute_note, execute_note_with_expected_outputs, execute_notes_and_build_chain};
+
+const BIDDING_PERIOD: u32 = 200;
+const REVEAL_PERIOD: u32 = 200;
+
+struct BidNotes {
+    bid_note: Note,
+    reveal_note: Note,
+    settle_note: Note,
+}
+
+fn refund_serial() -> Word {
+    Word::new([Felt::new(11), Felt::new(12), Felt::new(13), Felt::new(14)])
+}
+
+// Creates the bid, reveal and settle notes of a sealed `bid` on `name` backed by `deposit`,
+// committed to the auction with `auction_nonce`
+async fn create_bid_notes_for_auction(ctx: &mut TestingContext, bidder: AccountId, name: &str, bid: u64, deposit: u64, auction_nonce: u64, secret: Word) -> anyhow::Result<BidNotes> {
+    let domain_word = encode_domain(name);
+    let token = ctx.fungible_asset.faucet_id();
+    let commitment = compute_bid_commitment(domain_word, bidder, bid, auction_nonce, secret);
+
+    let deposit = FungibleAsset::new(token, deposit)?;
+    let bid_note = create_bid_note(bidder, ctx.naming.id(), token, domain_word, commitment, NoteAssets::new(vec![deposit.into()])?, refund_serial()).await?;
+    let reveal_note = create_reveal_bid_note(bidder, ctx.naming.id(), domain_word, bid, auction_nonce, secret).await?;
+    let settle_note = create_settle_bid_note(bidder, ctx.naming.id(), domain_word, bid, auction_nonce, secret, refund_serial()).await?;
+    for note in [&bid_note, &reveal_note, &settle_note] {
+        add_note_to_builder(&mut ctx.builder, note.clone())?;
+    }
+    Ok(BidNotes { bid_note, reveal_note, settle_note })
+}
+
+// Same as create_bid_notes_for_auction, for the first auction of `name`
+async fn create_bid_notes(ctx: &mut TestingContext, bidder: AccountId, name: &str, bid: u64, deposit: u64, secret: Word) -> anyhow::Result<BidNotes> {
+    create_bid_notes_for_auction(ctx, bidder, name, bid, deposit, 1, secret).await
+}
+
+// Makes every 3 letter domain auction-only. `extra_notes` are added to the chain without being executed
+async fn setup_auction(ctx: &mut TestingContext, extra_notes: &[&Note]) -> anyhow::Result<MockChain> {
+    let auction_only_note = create_set_auction_only_note(ctx.owner.id(), ctx.naming.id(), auction_length_key(3), true).await?;
+    let periods_note = create_set_auction_periods_note(ctx.owner.id(), ctx.naming.id(), BIDDING_PERIOD, REVEAL_PERIOD).await?;
+    add_note_to_builder(&mut ctx.builder, auction_only_note.clone())?;
+    add_note_to_builder(&mut ctx.builder, periods_note.clone())?;
+    for note in extra_notes {
+        add_note_to_builder(&mut ctx.builder, (*note).clone())?;
+    }
+
+    let builder = std::mem::replace(&mut ctx.builder, MockChain::builder());
+    execute_notes_and_build_chain(builder, &[ctx.initialize_note.id(), ctx.set_prices_note.id(), auction_only_note.id(), periods_note.id()], &mut ctx.naming).await
+}
+
+// Returns (bidding_end, reveal_end) of the auction for `name`
+fn get_auction_ends(ctx: &TestingContext, name: &str) -> anyhow::Result<(u64, u64)> {
+    let auction = ctx.naming.storage().get_map_item(22, encode_domain(name))?;
+    Ok((auction.get(2).unwrap().as_int(), auction.get(3).unwrap().as_int()))
+}
+
+async fn settle_with_refund(ctx: &mut TestingContext, chain: &mut MockChain, notes: &BidNotes, bidder: AccountId, refund: u64) -> anyhow::Result<()> {
+    let change = FungibleAsset::new(ctx.fungible_asset.faucet_id(), refund)?;
+    let refund_note = create_p2id_note_exact(ctx.naming.id(), bidder, vec![change.into()], NoteType::Public, Felt::new(0), refund_serial())?;
+
+    let executed_tx = execute_note_with_expected_outputs(chain, notes.settle_note.id(), &mut ctx.naming, vec![refund_note.clone()]).await?;
+    assert_eq!(executed_tx.output_notes().get_note(0).id(), refund_note.id());
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_auction_highest_bid_wins() -> anyhow::Result<()> {
+    let mut ctx = init_naming().await?;
+    let (registrar_1, registrar_2, registrar_3) = (ctx.registrar_1.id(), ctx.registrar_2.id(), ctx.registrar_3.id());
+    let bid_1 = create_bid_notes(&mut ctx, registrar_1, "abc", 1000, 1500, Word::new([Felt::new(1), Felt::new(2), Felt::new(3), Felt::new(4)])).await?;
+    let bid_2 = create_bid_notes(&mut ctx, registrar_2, "abc", 2000, 2000, Word::new([Felt::new(5), Felt::new(6), Felt::new(7), Felt::new(8)])).await?;
+    // Never revealed
+    let bid_3 = create_bid_notes(&mut ctx, registrar_3, "abc", 850, 900, Word::new([Felt::new(9), Felt::new(10), Felt::new(11), Felt::new(12)])).await?;
+    let mut chain = setup_auction(&mut ctx, &[]).await?;
+
+    for notes in [&bid_1, &bid_2, &bid_3] {
+        execute_note(&mut chain, notes.bid_note.id(), &mut ctx.naming).await?;
+    }
+    let token_word = Word::new([ctx.fungible_asset.faucet_id().suffix(), ctx.fungible_asset.faucet_id().prefix().as_felt(), Felt::new(0), Felt::new(0)]);
+    assert_eq!(ctx.naming.storage().get_map_item(25, token_word)?.first().unwrap().as_int(), 1500 + 2000 + 900);
+
+    // Bids stay sealed until the bidding phase is over
+    let result = execute_note(&mut chain, bid_1.reveal_note.id(), &mut ctx.naming).await;
+    assert!(result.is_err(), "Expected reveal during bidding to fail, but it succeeded");
+
+    let (bidding_end, reveal_end) = get_auction_ends(&ctx, "abc")?;
+    advance_to_timestamp(&mut chain, bidding_end)?;
+    execute_note(&mut chain, bid_1.reveal_note.id(), &mut ctx.naming).await?;
+    execute_note(&mut chain, bid_2.reveal_note.id(), &mut ctx.naming).await?;
+
+    let result = execute_note(&mut chain, bid_2.settle_note.id(), &mut ctx.naming).await;
+    assert!(result.is_err(), "Expected settle during reveal to fail, but it succeeded");
+
+    // Winner pays exactly its bid, nothing is refunded
+    advance_to_timestamp(&mut chain, reveal_end)?;
+    execute_note(&mut chain, bid_2.settle_note.id(), &mut ctx.naming).await?;
+
+    let domain_owner_slot = ctx.naming.storage().get_map_item(5, encode_domain("abc"))?;
+    assert_eq!(domain_owner_slot.first().unwrap().as_int(), registrar_2.suffix().as_int());
+    assert_eq!(domain_owner_slot.get(1).unwrap().as_int(), registrar_2.prefix().as_u64());
+    assert_eq!(ctx.naming.storage().get_map_item(10, token_word)?.first().unwrap().as_int(), 2000);
+
+    // Losing and unrevealed bids get their whole deposit back
+    settle_with_refund(&mut ctx, &mut chain, &bid_1, registrar_1, 1500).await?;
+    settle_with_refund(&mut ctx, &mut chain, &bid_3, registrar_3, 900).await?;
+    assert_eq!(ctx.naming.storage().get_map_item(25, token_word)?.first().unwrap().as_int(), 0);
+
+    // A bid can only be settled once
+    let result = execute_note(&mut chain, bid_1.settle_note.id(), &mut ctx.naming).await;
+    assert!(result.is_err(), "Expected second settle to fail, but it succeeded");
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_register_auction_only_domain_fails() -> anyhow::Result<()> {
+    let mut ctx = init_naming().await?;
+    let token = ctx.fungible_asset.faucet_id();
+    let cost = FungibleAsset::new(token, 789)?;
+    let register_note = create_register_note(ctx.registrar_1.id(), ctx.naming.id(), token, encode_domain("abc"), 1, None, NoteAssets::new(vec![cost.into()])?, refund_serial()).await?;
+    let mut chain = setup_auction(&mut ctx, &[&register_note]).await?;
+
+    let result = execute_note(&mut chain, register_note.id(), &mut ctx.naming).await;
+    assert!(result.is_err(), "Expected register of an auction-only domain to fail, but it succeeded");
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_reveal_bid_below_price_fails() -> anyhow::Result<()> {
+    let mut ctx = init_naming().await?;
+    let registrar_1 = ctx.registrar_1.id();
+    // Registering "abc" for a year costs 789
+    let notes = create_bid_notes(&mut ctx, registrar_1, "abc", 500, 1000, Word::new([Felt::new(1), Felt::new(2), Felt::new(3), Felt::new(4)])).await?;
+    let mut chain = setup_auction(&mut ctx, &[]).await?;
+
+    execute_note(&mut chain, notes.bid_note.id(), &mut ctx.naming).await?;
+    let (bidding_end, _) = get_auction_ends(&ctx, "abc")?;
+    advance_to_timestamp(&mut chain, bidding_end)?;
+
+    let result = execute_note(&mut chain, notes.reveal_note.id(), &mut ctx.naming).await;
+    assert!(result.is_err(), "Expected reveal of a bid below the price to fail, but it succeeded");
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_reveal_bid_for_other_auction_fails() -> anyhow::Result<()> {
+    let mut ctx = init_naming().await?;
+    let registrar_1 = ctx.registrar_1.id();
+    // Committed to the second auction of "abc" while the first one runs
+    let notes = create_bid_notes_for_auction(&mut ctx, registrar_1, "abc", 1000, 1000, 2, Word::new([Felt::new(1), Felt::new(2), Felt::new(3), Felt::new(4)])).await?;
+    let mut chain = setup_auction(&mut ctx, &[]).await?;
+
+    execute_note(&mut chain, notes.bid_note.id(), &mut ctx.naming).await?;
+    assert_eq!(ctx.naming.storage().get_map_item(27, encode_domain("abc"))?.first().unwrap().as_int(), 1);
+    let (bidding_end, reveal_end) = get_auction_ends(&ctx, "abc")?;
+    advance_to_timestamp(&mut chain, bidding_end)?;
+
+    let result = execute_note(&mut chain, notes.reveal_note.id(), &mut ctx.naming).await;
+    assert!(result.is_err(), "Expected reveal of a bid for another auction to fail, but it succeeded");
+
+    // The deposit is still refunded
+    advance_to_timestamp(&mut chain, reveal_end)?;
+    settle_with_refund(&mut ctx, &mut chain, &notes, registrar_1, 1000).await?;
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_bid_on_regular_domain_fails() -> anyhow::Result<()> {
+    let mut ctx = init_naming().await?;
+    let registrar_1 = ctx.registrar_1.id();
+    let notes = create_bid_notes(&mut ctx, registrar_1, "test", 1000, 1000, Word::new([Felt::new(1), Felt::new(2), Felt::new(3), Felt::new(4)])).await?;
+    let mut chain = setup_auction(&mut ctx, &[]).await?;
+
+    let result = execute_note(&mut chain, notes.bid_note.id(), &mut ctx.naming).await;
+    assert!(result.is_err(), "Expected bid on a regular domain to fail, but it succeeded");
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_set_auction_only_not_owner() -> anyhow::Result<()> {
+    let mut ctx = init_naming().await?;
+    let note = create_set_auction_only_note(ctx.registrar_1.id(), ctx.naming.id(), encode_domain("test"), true).await?;
+    let mut chain = setup_auction(&mut ctx, &[&note]).await?;
+
+    let result = execute_note(&mut chain, note.id(), &mut ctx.naming).await;
+    assert!(result.is_err(), "Expected set_auction_only from non-owner to fail, but it succeeded");
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_bid_before_winner_settled_fails() -> anyhow::Result<()> {
+    let mut ctx = init_naming().await?;
+    let (registrar_1, registrar_3) = (ctx.registrar_1.id(), ctx.registrar_3.id());
+    let winner = create_bid_notes(&mut ctx, registrar_1, "abc", 1000, 1000, Word::new([Felt::new(1), Felt::new(2), Felt::new(3), Felt::new(4)])).await?;
+    // Would start the second auction of "abc" with a 1 unit deposit
+    let griefer = create_bid_notes_for_auction(&mut ctx, registrar_3, "abc", 1, 1, 2, Word::new([Felt::new(5), Felt::new(6), Felt::new(7), Felt::new(8)])).await?;
+    let mut chain = setup_auction(&mut ctx, &[]).await?;
+
+    execute_note(&mut chain, winner.bid_note.id(), &mut ctx.naming).await?;
+    let (bidding_end, reveal_end) = get_auction_ends(&ctx, "abc")?;
+    advance_to_timestamp(&mut chain, bidding_end)?;
+    execute_note(&mut chain, winner.reveal_note.id(), &mut ctx.naming).await?;
+    advance_to_timestamp(&mut chain, reveal_end)?;
+
+    let result = execute_note(&mut chain, griefer.bid_note.id(), &mut ctx.naming).await;
+    assert!(result.is_err(), "Expected bid before the winner settled to fail, but it succeeded");
+
+    // The winner still gets the domain
+    execute_note(&mut chain, winner.settle_note.id(), &mut ctx.naming).await?;
+    let domain_owner_slot = ctx.naming.storage().get_map_item(5, encode_domain("abc"))?;
+    assert_eq!(domain_owner_slot.first().unwrap().as_int(), registrar_1.suffix().as_int());
+    assert_eq!(domain_owner_slot.get(1).unwrap().as_int(), registrar_1.prefix().as_u64());
+    Ok(())
+}
@@ -0,0 +1,208 @@
+mod test_utils;
+
+use miden_client::{account::AccountId, asset::FungibleAsset, note::{Note, NoteAssets, NoteInputs}};
+use miden_crypto::{Felt, Word};
+use miden_testing::MockChain;
+use midenname_contracts::{domain::encode_domain, notes::{compute_registration_commitment, create_commit_note, create_register_note, create_register_with_commitment_note, create_set_commitment_ages_note, generate_commitment_secret}, storage::{commitment_ages, referrer_revenue_key, DEFAULT_MAX_COMMITMENT_AGE, DEFAULT_MIN_COMMITMENT_AGE}};
+use test_utils::{init_naming, TestingContext};
+
+use crate::test_utils::{add_note_to_builder, create_note_for_naming, execute_note, execute_notes_and_build_chain};
+
+// Blocks to wait between commit and reveal, the mock chain moves 10 seconds per block
+const COMMITMENT_WAIT_BLOCKS: u32 = 20;
+
+fn test_refund_serial() -> Word {
+    Word::new([Felt::new(11), Felt::new(12), Felt::new(13), Felt::new(14)])
+}
+
+async fn create_test_reveal_note(ctx: &TestingContext, sender: AccountId, secret: Word) -> anyhow::Result<Note> {
+    let cost = FungibleAsset::new(ctx.fungible_asset.faucet_id(), 555)?;
+    create_register_with_commitment_note(sender, ctx.naming.id(), ctx.fungible_asset.faucet_id(), encode_domain("test"), 1, None, None, secret, NoteAssets::new(vec![cost.into()])?, test_refund_serial()).await
+}
+
+// Sets the commitment ages, executes `setup_notes`, commits to "test" for registrar_1 with `secret`
+// and adds `reveal_note` to the chain
+async fn setup_commitment(ctx: &mut TestingContext, min_age: u32, max_age: u32, setup_notes: &[Note], secret: Word, reveal_note: &Note) -> anyhow::Result<MockChain> {
+    let ages_note = create_set_commitment_ages_note(ctx.owner.id(), ctx.naming.id(), min_age, max_age).await?;
+    let commitment = compute_registration_commitment(encode_domain("test"), ctx.registrar_1.id(), secret);
+    let commit_note = create_commit_note(ctx.registrar_1.id(), ctx.naming.id(), commitment).await?;
+    let mut note_ids = vec![ctx.initialize_note.id(), ctx.set_prices_note.id(), ages_note.id()];
+    add_note_to_builder(&mut ctx.builder, ages_note)?;
+    for note in setup_notes {
+        add_note_to_builder(&mut ctx.builder, note.clone())?;
+        note_ids.push(note.id());
+    }
+    note_ids.push(commit_note.id());
+    add_note_to_builder(&mut ctx.builder, commit_note)?;
+    add_note_to_builder(&mut ctx.builder, reveal_note.clone())?;
+
+    let builder = std::mem::replace(&mut ctx.builder, MockChain::builder());
+    let chain = execute_notes_and_build_chain(builder, &note_ids, &mut ctx.naming).await?;
+
+    assert_eq!(ctx.naming.storage().get_item(28)?, commitment_ages(min_age, max_age));
+    let commit_time = ctx.naming.storage().get_map_item(16, commitment)?;
+    assert!(commit_time.first().unwrap().as_int() >= 1700000000);
+    Ok(chain)
+}
+
+fn wait_for_commitment(chain: &mut MockChain) -> anyhow::Result<()> {
+    let block_num = chain.latest_block_header().block_num().as_u32();
+    chain.prove_until_block(block_num + COMMITMENT_WAIT_BLOCKS)?;
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_register_with_commitment() -> anyhow::Result<()> {
+    let mut ctx = init_naming().await?;
+    let secret = generate_commitment_secret();
+    let reveal_note = create_test_reveal_note(&ctx, ctx.registrar_1.id(), secret).await?;
+    let mut chain = setup_commitment(&mut ctx, DEFAULT_MIN_COMMITMENT_AGE, DEFAULT_MAX_COMMITMENT_AGE, &[], secret, &reveal_note).await?;
+    wait_for_commitment(&mut chain)?;
+
+    execute_note(&mut chain, reveal_note.id(), &mut ctx.naming).await?;
+
+    let domain_word = encode_domain("test");
+    let domain_owner_slot = ctx.naming.storage().get_map_item(5, domain_word)?;
+    assert_eq!(domain_owner_slot.first().unwrap().as_int(), ctx.registrar_1.id().suffix().as_int());
+    assert_eq!(domain_owner_slot.get(1).unwrap().as_int(), ctx.registrar_1.id().prefix().as_u64());
+
+    let total_domain_count = ctx.naming.storage().get_item(9)?;
+    assert_eq!(total_domain_count.first().unwrap().as_int(), 1);
+
+    // Commitment can not be revealed twice
+    let commitment = compute_registration_commitment(domain_word, ctx.registrar_1.id(), secret);
+    let commit_time = ctx.naming.storage().get_map_item(16, commitment)?;
+    assert_eq!(commit_time.first().unwrap().as_int(), 0);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_register_with_commitment_too_early() -> anyhow::Result<()> {
+    let mut ctx = init_naming().await?;
+    let secret = generate_commitment_secret();
+    let reveal_note = create_test_reveal_note(&ctx, ctx.registrar_1.id(), secret).await?;
+    let mut chain = setup_commitment(&mut ctx, DEFAULT_MIN_COMMITMENT_AGE, DEFAULT_MAX_COMMITMENT_AGE, &[], secret, &reveal_note).await?;
+
+    let result = execute_note(&mut chain, reveal_note.id(), &mut ctx.naming).await;
+
+    assert!(result.is_err(), "Expected reveal before the minimum commitment age to fail, but it succeeded");
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_register_with_expired_commitment() -> anyhow::Result<()> {
+    let mut ctx = init_naming().await?;
+    let secret = generate_commitment_secret();
+    let reveal_note = create_test_reveal_note(&ctx, ctx.registrar_1.id(), secret).await?;
+    // Commitments expire after 100 seconds, waiting 20 blocks takes 200
+    let mut chain = setup_commitment(&mut ctx, 0, 100, &[], secret, &reveal_note).await?;
+    wait_for_commitment(&mut chain)?;
+
+    let result = execute_note(&mut chain, reveal_note.id(), &mut ctx.naming).await;
+
+    assert!(result.is_err(), "Expected reveal after the maximum commitment age to fail, but it succeeded");
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_register_with_commitment_wrong_secret() -> anyhow::Result<()> {
+    let mut ctx = init_naming().await?;
+    let secret = generate_commitment_secret();
+    let reveal_note = create_test_reveal_note(&ctx, ctx.registrar_1.id(), Word::new([Felt::new(1), Felt::new(2), Felt::new(3), Felt::new(4)])).await?;
+    let mut chain = setup_commitment(&mut ctx, DEFAULT_MIN_COMMITMENT_AGE, DEFAULT_MAX_COMMITMENT_AGE, &[], secret, &reveal_note).await?;
+    wait_for_commitment(&mut chain)?;
+
+    let result = execute_note(&mut chain, reveal_note.id(), &mut ctx.naming).await;
+
+    assert!(result.is_err(), "Expected reveal with a wrong secret to fail, but it succeeded");
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_register_with_commitment_by_front_runner() -> anyhow::Result<()> {
+    let mut ctx = init_naming().await?;
+    let secret = generate_commitment_secret();
+    // registrar_2 copies the secret of registrar_1's commitment
+    let reveal_note = create_test_reveal_note(&ctx, ctx.registrar_2.id(), secret).await?;
+    let mut chain = setup_commitment(&mut ctx, DEFAULT_MIN_COMMITMENT_AGE, DEFAULT_MAX_COMMITMENT_AGE, &[], secret, &reveal_note).await?;
+    wait_for_commitment(&mut chain)?;
+
+    let result = execute_note(&mut chain, reveal_note.id(), &mut ctx.naming).await;
+
+    assert!(result.is_err(), "Expected reveal by an account other than the committer to fail, but it succeeded");
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_register_with_commitment_for_owner_with_referrer() -> anyhow::Result<()> {
+    let mut ctx = init_naming().await?;
+    let set_ref_rate_inputs = NoteInputs::new(vec![
+        Felt::new(2000),
+        Felt::new(0),
+        Felt::new(0),
+        Felt::new(0),
+        ctx.registrar_2.id().suffix(),
+        ctx.registrar_2.id().prefix().as_felt(),
+        Felt::new(0),
+        Felt::new(0),
+    ])?;
+    let set_ref_rate_note = create_note_for_naming("set_referrer_rate".to_string(), set_ref_rate_inputs, ctx.owner.id(), ctx.naming.id(), NoteAssets::new(vec![])?).await?;
+    let secret = generate_commitment_secret();
+    let cost = FungibleAsset::new(ctx.fungible_asset.faucet_id(), 555)?;
+    let reveal_note = create_register_with_commitment_note(ctx.registrar_1.id(), ctx.naming.id(), ctx.fungible_asset.faucet_id(), encode_domain("test"), 1, Some(ctx.registrar_3.id()), Some(ctx.registrar_2.id()), secret, NoteAssets::new(vec![cost.into()])?, test_refund_serial()).await?;
+    let mut chain = setup_commitment(&mut ctx, DEFAULT_MIN_COMMITMENT_AGE, DEFAULT_MAX_COMMITMENT_AGE, &[set_ref_rate_note], secret, &reveal_note).await?;
+    wait_for_commitment(&mut chain)?;
+
+    execute_note(&mut chain, reveal_note.id(), &mut ctx.naming).await?;
+
+    // The committer pays, the domain goes to the owner
+    let domain_owner_slot = ctx.naming.storage().get_map_item(5, encode_domain("test"))?;
+    assert_eq!(domain_owner_slot.first().unwrap().as_int(), ctx.registrar_3.id().suffix().as_int());
+    assert_eq!(domain_owner_slot.get(1).unwrap().as_int(), ctx.registrar_3.id().prefix().as_u64());
+
+    let referrer_revenue = ctx.naming.storage().get_map_item(7, referrer_revenue_key(ctx.registrar_2.id(), ctx.fungible_asset.faucet_id()))?;
+    assert_eq!(referrer_revenue.first().unwrap().as_int(), 111);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_register_without_commitment() -> anyhow::Result<()> {
+    let mut ctx = init_naming().await?;
+    let ages_note = create_set_commitment_ages_note(ctx.owner.id(), ctx.naming.id(), DEFAULT_MIN_COMMITMENT_AGE, DEFAULT_MAX_COMMITMENT_AGE).await?;
+    let cost = FungibleAsset::new(ctx.fungible_asset.faucet_id(), 555)?;
+    let register_note = create_register_note(ctx.registrar_1.id(), ctx.naming.id(), ctx.fungible_asset.faucet_id(), encode_domain("test"), 1, None, NoteAssets::new(vec![cost.into()])?, test_refund_serial()).await?;
+    add_note_to_builder(&mut ctx.builder, ages_note.clone())?;
+    add_note_to_builder(&mut ctx.builder, register_note.clone())?;
+    let mut chain = execute_notes_and_build_chain(ctx.builder, &[ctx.initialize_note.id(), ctx.set_prices_note.id(), ages_note.id()], &mut ctx.naming).await?;
+
+    let result = execute_note(&mut chain, register_note.id(), &mut ctx.naming).await;
+
+    assert!(result.is_err(), "Expected register without a commitment to fail while the min commitment age is set, but it succeeded");
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_set_commitment_ages_by_not_owner() -> anyhow::Result<()> {
+    let mut ctx = init_naming().await?;
+    let ages_note = create_set_commitment_ages_note(ctx.registrar_1.id(), ctx.naming.id(), 0, 10).await?;
+    add_note_to_builder(&mut ctx.builder, ages_note.clone())?;
+    let mut chain = execute_notes_and_build_chain(ctx.builder, &[ctx.initialize_note.id(), ctx.set_prices_note.id()], &mut ctx.naming).await?;
+
+    let result = execute_note(&mut chain, ages_note.id(), &mut ctx.naming).await;
+
+    assert!(result.is_err(), "Expected set_commitment_ages by non-owner to fail, but it succeeded");
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_set_commitment_ages_min_not_below_max() -> anyhow::Result<()> {
+    let mut ctx = init_naming().await?;
+    let ages_note = create_set_commitment_ages_note(ctx.owner.id(), ctx.naming.id(), 600, 600).await?;
+    add_note_to_builder(&mut ctx.builder, ages_note.clone())?;
+    let mut chain = execute_notes_and_build_chain(ctx.builder, &[ctx.initialize_note.id(), ctx.set_prices_note.id()], &mut ctx.naming).await?;
+
+    let result = execute_note(&mut chain, ages_note.id(), &mut ctx.naming).await;
+
+    assert!(result.is_err(), "Expected set_commitment_ages with min age not below max age to fail, but it succeeded");
+    Ok(())
+}
@@ -0,0 +1,126 @@
+mod test_utils;
+
+use miden_client::{account::AccountId, asset::FungibleAsset, note::{NoteAssets, NoteInputs}, testing::account_id::ACCOUNT_ID_PUBLIC_FUNGIBLE_FAUCET_2};
+use miden_crypto::{Felt, Word};
+use miden_testing::MockChain;
+use midenname_contracts::{domain::encode_domain, notes::{create_register_note, create_set_primary_name_note}};
+use test_utils::{init_naming, TestingContext};
+
+use crate::test_utils::{add_note_to_builder, create_note_for_naming, execute_naming_fpi_call, execute_notes_and_build_chain, words_to_stack};
+
+// Registers `name` for one year from registrar_1, links it to registrar_1 and makes it its primary name
+async fn setup_active_domain(ctx: &mut TestingContext, name: &str) -> anyhow::Result<MockChain> {
+    let refund_serial = Word::new([Felt::new(11), Felt::new(12), Felt::new(13), Felt::new(14)]);
+    let domain_word = encode_domain(name);
+
+    let cost = FungibleAsset::new(ctx.fungible_asset.faucet_id(), 555)?;
+    let register_note = create_register_note(ctx.registrar_1.id(), ctx.naming.id(), ctx.fungible_asset.faucet_id(), domain_word, 1, None, NoteAssets::new(vec![cost.into()])?, refund_serial).await?;
+    let activate_note = create_note_for_naming("activate_domain".to_string(), NoteInputs::new(domain_word.to_vec())?, ctx.registrar_1.id(), ctx.naming.id(), NoteAssets::new(vec![])?).await?;
+    add_note_to_builder(&mut ctx.builder, register_note.clone())?;
+    add_note_to_builder(&mut ctx.builder, activate_note.clone())?;
+    let primary_note = create_set_primary_name_note(ctx.registrar_1.id(), ctx.naming.id(), domain_word).await?;
+    add_note_to_builder(&mut ctx.builder, primary_note.clone())?;
+
+    let builder = std::mem::replace(&mut ctx.builder, MockChain::builder());
+    execute_notes_and_build_chain(builder, &[ctx.initialize_note.id(), ctx.set_prices_note.id(), register_note.id(), activate_note.id(), primary_note.id()], &mut ctx.naming).await
+}
+
+fn account_id_word(id: AccountId) -> Word {
+    Word::new([id.suffix(), id.prefix().as_felt(), Felt::new(0), Felt::new(0)])
+}
+
+#[tokio::test]
+async fn test_getters_for_active_domain() -> anyhow::Result<()> {
+    let mut ctx = init_naming().await?;
+    let chain = setup_active_domain(&mut ctx, "test").await?;
+    let domain_word = encode_domain("test");
+    let account = account_id_word(ctx.registrar_1.id());
+    let expiry = ctx.naming.storage().get_map_item(12, domain_word)?.first().unwrap().as_int();
+
+    execute_naming_fpi_call(&chain, &ctx.fpi_reader, &ctx.naming, "resolve", &words_to_stack(&[domain_word]), &words_to_stack(&[account])).await?;
+    execute_naming_fpi_call(&chain, &ctx.fpi_reader, &ctx.naming, "reverse", &words_to_stack(&[account]), &words_to_stack(&[domain_word])).await?;
+    execute_naming_fpi_call(&chain, &ctx.fpi_reader, &ctx.naming, "get_owner", &words_to_stack(&[domain_word]), &words_to_stack(&[account])).await?;
+    execute_naming_fpi_call(&chain, &ctx.fpi_reader, &ctx.naming, "get_expiry", &words_to_stack(&[domain_word]), &[Felt::new(expiry)]).await?;
+    execute_naming_fpi_call(&chain, &ctx.fpi_reader, &ctx.naming, "is_available", &words_to_stack(&[domain_word]), &[Felt::new(0)]).await?;
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_getters_for_expired_domain() -> anyhow::Result<()> {
+    let mut ctx = init_naming().await?;
+    let mut chain = setup_active_domain(&mut ctx, "test").await?;
+    let domain_word = encode_domain("test");
+    let account = account_id_word(ctx.registrar_1.id());
+    let expiry = ctx.naming.storage().get_map_item(12, domain_word)?.first().unwrap().as_int();
+
+    // Expired but not cleared yet, the mappings are still in storage
+    chain.prove_until_block(100)?;
+    assert_eq!(ctx.naming.storage().get_map_item(4, domain_word)?, account);
+
+    execute_naming_fpi_call(&chain, &ctx.fpi_reader, &ctx.naming, "resolve", &words_to_stack(&[domain_word]), &words_to_stack(&[Word::default()])).await?;
+    execute_naming_fpi_call(&chain, &ctx.fpi_reader, &ctx.naming, "reverse", &words_to_stack(&[account]), &words_to_stack(&[Word::default()])).await?;
+    execute_naming_fpi_call(&chain, &ctx.fpi_reader, &ctx.naming, "get_owner", &words_to_stack(&[domain_word]), &words_to_stack(&[Word::default()])).await?;
+    execute_naming_fpi_call(&chain, &ctx.fpi_reader, &ctx.naming, "get_expiry", &words_to_stack(&[domain_word]), &[Felt::new(expiry)]).await?;
+    execute_naming_fpi_call(&chain, &ctx.fpi_reader, &ctx.naming, "is_available", &words_to_stack(&[domain_word]), &[Felt::new(1)]).await?;
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_getters_for_unregistered_domain() -> anyhow::Result<()> {
+    let mut ctx = init_naming().await?;
+    let chain = setup_active_domain(&mut ctx, "test").await?;
+    let domain_word = encode_domain("other");
+
+    execute_naming_fpi_call(&chain, &ctx.fpi_reader, &ctx.naming, "resolve", &words_to_stack(&[domain_word]), &words_to_stack(&[Word::default()])).await?;
+    execute_naming_fpi_call(&chain, &ctx.fpi_reader, &ctx.naming, "get_owner", &words_to_stack(&[domain_word]), &words_to_stack(&[Word::default()])).await?;
+    execute_naming_fpi_call(&chain, &ctx.fpi_reader, &ctx.naming, "get_expiry", &words_to_stack(&[domain_word]), &[Felt::new(0)]).await?;
+    execute_naming_fpi_call(&chain, &ctx.fpi_reader, &ctx.naming, "is_available", &words_to_stack(&[domain_word]), &[Felt::new(1)]).await?;
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_getter_outputs_are_checked() -> anyhow::Result<()> {
+    let mut ctx = init_naming().await?;
+    let chain = setup_active_domain(&mut ctx, "test").await?;
+
+    let result = execute_naming_fpi_call(&chain, &ctx.fpi_reader, &ctx.naming, "is_available", &words_to_stack(&[encode_domain("test")]), &[Felt::new(1)]).await;
+
+    assert!(result.is_err(), "Expected wrong output to fail the script, but it succeeded");
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_quote_price() -> anyhow::Result<()> {
+    let mut ctx = init_naming().await?;
+    let chain = setup_active_domain(&mut ctx, "test").await?;
+    let token = account_id_word(ctx.fungible_asset.faucet_id());
+    let reg_len = |years: u64| Word::new([Felt::new(years), Felt::new(0), Felt::new(0), Felt::new(0)]);
+
+    // Same amounts the registration accepts, see the discount tests
+    for (name, years, price) in [("test", 1, 555), ("test", 3, 1167), ("test", 5, 1390), ("a", 1, 123123), ("abcdefghijklmnopqrstuvwxyz", 1, 123)] {
+        execute_naming_fpi_call(&chain, &ctx.fpi_reader, &ctx.naming, "quote_price", &words_to_stack(&[encode_domain(name), token, reg_len(years)]), &[Felt::new(price)]).await?;
+    }
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_quote_price_rejects_invalid_requests() -> anyhow::Result<()> {
+    let mut ctx = init_naming().await?;
+    let chain = setup_active_domain(&mut ctx, "test").await?;
+    let second_token = account_id_word(ACCOUNT_ID_PUBLIC_FUNGIBLE_FAUCET_2.try_into()?);
+    let token = account_id_word(ctx.fungible_asset.faucet_id());
+    let reg_len = |years: u64| Word::new([Felt::new(years), Felt::new(0), Felt::new(0), Felt::new(0)]);
+    let bad_length = Word::new([Felt::new(0), Felt::new(0), Felt::new(1), Felt::new(2)]);
+
+    let cases = [
+        ("token without prices", [encode_domain("test"), second_token, reg_len(1)]),
+        ("more than 10 years", [encode_domain("test"), token, reg_len(11)]),
+        ("malformed domain", [bad_length, token, reg_len(1)]),
+    ];
+
+    for (case, inputs) in cases {
+        let result = execute_naming_fpi_call(&chain, &ctx.fpi_reader, &ctx.naming, "quote_price", &words_to_stack(&inputs), &[]).await;
+        assert!(result.is_err(), "Expected quote with {} to fail, but it succeeded", case);
+    }
+    Ok(())
+}
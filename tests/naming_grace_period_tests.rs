@@ -0,0 +1,125 @@
+mod test_utils;
+
+use miden_client::{account::AccountId, asset::FungibleAsset, note::{Note, NoteAssets, NoteInputs}};
+use miden_crypto::{Felt, Word};
+use miden_testing::MockChain;
+use midenname_contracts::{domain::encode_domain, notes::{create_extend_domain_note, create_register_note, create_set_grace_period_note}};
+use test_utils::{init_naming, TestingContext};
+
+use crate::test_utils::{add_note_to_builder, advance_to_timestamp, create_note_for_naming, execute_naming_fpi_call, execute_note, execute_notes_and_build_chain, words_to_stack};
+
+const GRACE_PERIOD: u32 = 300;
+
+struct GraceNotes {
+    extend_note: Note,
+    rebuy_note: Note,
+}
+
+// Sets the grace period, registers "test" for one year from registrar_1 and links it to registrar_1.
+// Returns an extend note from registrar_1 and a register note from registrar_2 for the same domain.
+async fn setup_grace_period(ctx: &mut TestingContext) -> anyhow::Result<(MockChain, GraceNotes)> {
+    let refund_serial = Word::new([Felt::new(11), Felt::new(12), Felt::new(13), Felt::new(14)]);
+    let domain_word = encode_domain("test");
+    let token = ctx.fungible_asset.faucet_id();
+    let cost = NoteAssets::new(vec![FungibleAsset::new(token, 555)?.into()])?;
+
+    let grace_note = create_set_grace_period_note(ctx.owner.id(), ctx.naming.id(), GRACE_PERIOD).await?;
+    let register_note = create_register_note(ctx.registrar_1.id(), ctx.naming.id(), token, domain_word, 1, None, cost.clone(), refund_serial).await?;
+    let activate_note = create_note_for_naming("activate_domain".to_string(), NoteInputs::new(domain_word.to_vec())?, ctx.registrar_1.id(), ctx.naming.id(), NoteAssets::new(vec![])?).await?;
+    let extend_note = create_extend_domain_note(ctx.registrar_1.id(), ctx.naming.id(), token, domain_word, 1, cost.clone(), refund_serial).await?;
+    let rebuy_note = create_register_note(ctx.registrar_2.id(), ctx.naming.id(), token, domain_word, 1, None, cost, refund_serial).await?;
+    for note in [&grace_note, &register_note, &activate_note, &extend_note, &rebuy_note] {
+        add_note_to_builder(&mut ctx.builder, note.clone())?;
+    }
+
+    let builder = std::mem::replace(&mut ctx.builder, MockChain::builder());
+    let chain = execute_notes_and_build_chain(builder, &[ctx.initialize_note.id(), ctx.set_prices_note.id(), grace_note.id(), register_note.id(), activate_note.id()], &mut ctx.naming).await?;
+
+    assert_eq!(ctx.naming.storage().get_item(17)?.first().unwrap().as_int(), GRACE_PERIOD as u64);
+    Ok((chain, GraceNotes { extend_note, rebuy_note }))
+}
+
+fn get_expiry(ctx: &TestingContext) -> anyhow::Result<u64> {
+    Ok(ctx.naming.storage().get_map_item(12, encode_domain("test"))?.first().unwrap().as_int())
+}
+
+fn account_id_word(id: AccountId) -> Word {
+    Word::new([id.suffix(), id.prefix().as_felt(), Felt::new(0), Felt::new(0)])
+}
+
+#[tokio::test]
+async fn test_grace_period_extend_by_previous_owner() -> anyhow::Result<()> {
+    let mut ctx = init_naming().await?;
+    let (mut chain, notes) = setup_grace_period(&mut ctx).await?;
+    let domain_word = encode_domain("test");
+    let expiry = get_expiry(&ctx)?;
+
+    // Expired but still inside the grace period
+    advance_to_timestamp(&mut chain, expiry)?;
+
+    execute_naming_fpi_call(&chain, &ctx.fpi_reader, &ctx.naming, "resolve", &words_to_stack(&[domain_word]), &words_to_stack(&[Word::default()])).await?;
+    execute_naming_fpi_call(&chain, &ctx.fpi_reader, &ctx.naming, "is_available", &words_to_stack(&[domain_word]), &[Felt::new(0)]).await?;
+
+    let result = execute_note(&mut chain, notes.rebuy_note.id(), &mut ctx.naming).await;
+    assert!(result.is_err(), "Expected register during the grace period to fail, but it succeeded");
+
+    execute_note(&mut chain, notes.extend_note.id(), &mut ctx.naming).await?;
+    assert_eq!(get_expiry(&ctx)?, expiry + ctx.one_year as u64);
+
+    // Resolution is back once renewed
+    let account = account_id_word(ctx.registrar_1.id());
+    execute_naming_fpi_call(&chain, &ctx.fpi_reader, &ctx.naming, "resolve", &words_to_stack(&[domain_word]), &words_to_stack(&[account])).await?;
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_grace_period_over() -> anyhow::Result<()> {
+    let mut ctx = init_naming().await?;
+    let (mut chain, notes) = setup_grace_period(&mut ctx).await?;
+    let expiry = get_expiry(&ctx)?;
+
+    advance_to_timestamp(&mut chain, expiry + GRACE_PERIOD as u64 + 1)?;
+
+    let result = execute_note(&mut chain, notes.extend_note.id(), &mut ctx.naming).await;
+    assert!(result.is_err(), "Expected extend after the grace period to fail, but it succeeded");
+
+    execute_note(&mut chain, notes.rebuy_note.id(), &mut ctx.naming).await?;
+
+    let domain_owner_slot = ctx.naming.storage().get_map_item(5, encode_domain("test"))?;
+    assert_eq!(domain_owner_slot.first().unwrap().as_int(), ctx.registrar_2.id().suffix().as_int());
+    assert_eq!(domain_owner_slot.get(1).unwrap().as_int(), ctx.registrar_2.id().prefix().as_u64());
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_grace_period_blocks_clear_expired_domain() -> anyhow::Result<()> {
+    let mut ctx = init_naming().await?;
+    let domain_word = encode_domain("test");
+    let clear_note = create_note_for_naming("clear_expired_domain".to_string(), NoteInputs::new(domain_word.to_vec())?, ctx.registrar_3.id(), ctx.naming.id(), NoteAssets::new(vec![])?).await?;
+    add_note_to_builder(&mut ctx.builder, clear_note.clone())?;
+    let (mut chain, _) = setup_grace_period(&mut ctx).await?;
+    let expiry = get_expiry(&ctx)?;
+
+    advance_to_timestamp(&mut chain, expiry)?;
+    let result = execute_note(&mut chain, clear_note.id(), &mut ctx.naming).await;
+    assert!(result.is_err(), "Expected clear during the grace period to fail, but it succeeded");
+
+    advance_to_timestamp(&mut chain, expiry + GRACE_PERIOD as u64)?;
+    execute_note(&mut chain, clear_note.id(), &mut ctx.naming).await?;
+
+    assert_eq!(ctx.naming.storage().get_map_item(5, domain_word)?, Word::default());
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_set_grace_period_by_not_owner() -> anyhow::Result<()> {
+    let mut ctx = init_naming().await?;
+    let grace_note = create_set_grace_period_note(ctx.registrar_1.id(), ctx.naming.id(), GRACE_PERIOD).await?;
+    add_note_to_builder(&mut ctx.builder, grace_note.clone())?;
+    let mut chain = execute_notes_and_build_chain(ctx.builder, &[ctx.initialize_note.id(), ctx.set_prices_note.id()], &mut ctx.naming).await?;
+
+    let result = execute_note(&mut chain, grace_note.id(), &mut ctx.naming).await;
+
+    assert!(result.is_err(), "Expected set grace period by not owner to fail, but it succeeded");
+    Ok(())
+}
@@ -0,0 +1,183 @@
+mod test_utils;
+
+use miden_client::{asset::FungibleAsset, note::{Note, NoteAssets, NoteInputs}};
+use miden_crypto::{Felt, Word};
+use miden_testing::MockChain;
+use midenname_contracts::{config::RegistryParameters, domain::encode_domain, notes::{build_initialize_inputs, create_register_note, create_register_with_referrer_note, create_set_parameters_note}};
+use test_utils::{init_naming, TestingContext};
+
+use crate::test_utils::{add_note_to_builder, create_note_for_naming, execute_note, execute_notes_and_build_chain};
+
+fn parameters(one_year: u32, max_registration_years: u32, max_name_length: u32, referral_rate_limit: u32) -> RegistryParameters {
+    RegistryParameters { one_year, max_registration_years, max_name_length, referral_rate_limit }
+}
+
+async fn create_register_test_note(ctx: &TestingContext, name: &str, reg_len: u64, cost: u64) -> anyhow::Result<Note> {
+    let token = ctx.fungible_asset.faucet_id();
+    let refund_serial = Word::new([Felt::new(11), Felt::new(12), Felt::new(13), Felt::new(14)]);
+    let cost = FungibleAsset::new(token, cost)?;
+    create_register_note(ctx.registrar_1.id(), ctx.naming.id(), token, encode_domain(name), reg_len, None, NoteAssets::new(vec![cost.into()])?, refund_serial).await
+}
+
+// Initializes the registry and sets prices. `extra_notes` are added to the chain without being executed
+async fn setup_parameters(ctx: &mut TestingContext, extra_notes: &[&Note]) -> anyhow::Result<MockChain> {
+    for note in extra_notes {
+        add_note_to_builder(&mut ctx.builder, (*note).clone())?;
+    }
+
+    let builder = std::mem::replace(&mut ctx.builder, MockChain::builder());
+    execute_notes_and_build_chain(builder, &[ctx.initialize_note.id(), ctx.set_prices_note.id()], &mut ctx.naming).await
+}
+
+#[tokio::test]
+async fn test_set_parameters_raises_max_years() -> anyhow::Result<()> {
+    let mut ctx = init_naming().await?;
+    let new_parameters = parameters(ctx.one_year, 20, 30, 2500);
+    let parameters_note = create_set_parameters_note(ctx.owner.id(), ctx.naming.id(), new_parameters).await?;
+    // 50% discount from 5 years, 278 per year
+    let register_note = create_register_test_note(&ctx, "test", 15, 278 * 15).await?;
+    let mut chain = setup_parameters(&mut ctx, &[&parameters_note, &register_note]).await?;
+
+    execute_note(&mut chain, parameters_note.id(), &mut ctx.naming).await?;
+    assert_eq!(ctx.naming.storage().get_item(13)?, new_parameters.to_word());
+
+    let current_time = chain.latest_block_header().timestamp();
+    execute_note(&mut chain, register_note.id(), &mut ctx.naming).await?;
+
+    let expiry = ctx.naming.storage().get_map_item(12, encode_domain("test"))?.first().unwrap().as_int();
+    assert_eq!(expiry, (current_time + ctx.one_year * 15) as u64);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_set_parameters_keeps_registration_window() -> anyhow::Result<()> {
+    let mut ctx = init_naming().await?;
+    let shorter_note = create_set_parameters_note(ctx.owner.id(), ctx.naming.id(), parameters(ctx.one_year, 5, 30, 2500)).await?;
+    let longer_years_note = create_set_parameters_note(ctx.owner.id(), ctx.naming.id(), parameters(ctx.one_year * 2, 5, 30, 2500)).await?;
+    let register_note = create_register_test_note(&ctx, "test", 6, 278 * 6).await?;
+    let mut chain = setup_parameters(&mut ctx, &[&shorter_note, &longer_years_note, &register_note]).await?;
+
+    let result = execute_note(&mut chain, shorter_note.id(), &mut ctx.naming).await;
+    assert!(result.is_err(), "Expected set_parameters shrinking the registration window to fail, but it succeeded");
+
+    // 5 years of twice the length keep the window
+    execute_note(&mut chain, longer_years_note.id(), &mut ctx.naming).await?;
+
+    let result = execute_note(&mut chain, register_note.id(), &mut ctx.naming).await;
+    assert!(result.is_err(), "Expected register above max years to fail, but it succeeded");
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_set_parameters_lower_max_name_length_fails() -> anyhow::Result<()> {
+    let mut ctx = init_naming().await?;
+    let parameters_note = create_set_parameters_note(ctx.owner.id(), ctx.naming.id(), parameters(ctx.one_year, 10, 21, 2500)).await?;
+    let mut chain = setup_parameters(&mut ctx, &[&parameters_note]).await?;
+
+    let result = execute_note(&mut chain, parameters_note.id(), &mut ctx.naming).await;
+    assert!(result.is_err(), "Expected set_parameters lowering the max name length to fail, but it succeeded");
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_max_name_length_limits_registration() -> anyhow::Result<()> {
+    let mut ctx = init_naming().await?;
+    let initialize_inputs = NoteInputs::new(build_initialize_inputs(ctx.owner.id(), parameters(ctx.one_year, 10, 4, 2500)))?;
+    let initialize_note = create_note_for_naming("initialize_naming".to_string(), initialize_inputs, ctx.owner.id(), ctx.naming.id(), NoteAssets::new(vec![])?).await?;
+    let parameters_note = create_set_parameters_note(ctx.owner.id(), ctx.naming.id(), parameters(ctx.one_year, 10, 5, 2500)).await?;
+    let register_note = create_register_test_note(&ctx, "tests", 1, 123).await?;
+    for note in [&initialize_note, &parameters_note, &register_note] {
+        add_note_to_builder(&mut ctx.builder, note.clone())?;
+    }
+
+    let builder = std::mem::replace(&mut ctx.builder, MockChain::builder());
+    let mut chain = execute_notes_and_build_chain(builder, &[initialize_note.id(), ctx.set_prices_note.id()], &mut ctx.naming).await?;
+
+    let result = execute_note(&mut chain, register_note.id(), &mut ctx.naming).await;
+    assert!(result.is_err(), "Expected register above the max name length to fail, but it succeeded");
+
+    execute_note(&mut chain, parameters_note.id(), &mut ctx.naming).await?;
+    execute_note(&mut chain, register_note.id(), &mut ctx.naming).await?;
+
+    let domain_owner_slot = ctx.naming.storage().get_map_item(5, encode_domain("tests"))?;
+    assert_eq!(domain_owner_slot.get(1).unwrap().as_int(), ctx.registrar_1.id().prefix().as_u64());
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_set_parameters_raises_referral_cap() -> anyhow::Result<()> {
+    let mut ctx = init_naming().await?;
+    let set_ref_rate_inputs = NoteInputs::new([
+        Felt::new(2800),
+        Felt::new(0),
+        Felt::new(0),
+        Felt::new(0),
+        ctx.registrar_2.id().suffix(),
+        ctx.registrar_2.id().prefix().as_felt(),
+        Felt::new(0),
+        Felt::new(0),
+    ].to_vec())?;
+    let set_ref_rate_note = create_note_for_naming("set_referrer_rate".to_string(), set_ref_rate_inputs, ctx.owner.id(), ctx.naming.id(), NoteAssets::new(vec![])?).await?;
+    let parameters_note = create_set_parameters_note(ctx.owner.id(), ctx.naming.id(), parameters(ctx.one_year, 10, 30, 3000)).await?;
+    let mut chain = setup_parameters(&mut ctx, &[&set_ref_rate_note, &parameters_note]).await?;
+
+    let result = execute_note(&mut chain, set_ref_rate_note.id(), &mut ctx.naming).await;
+    assert!(result.is_err(), "Expected referrer rate above the cap to fail, but it succeeded");
+
+    execute_note(&mut chain, parameters_note.id(), &mut ctx.naming).await?;
+    execute_note(&mut chain, set_ref_rate_note.id(), &mut ctx.naming).await?;
+
+    let ref_rate_slot = ctx.naming.storage().get_map_item(6, Word::new([ctx.registrar_2.id().suffix(), ctx.registrar_2.id().prefix().as_felt(), Felt::new(0), Felt::new(0)]))?;
+    assert_eq!(ref_rate_slot.first().unwrap().as_int(), 2800);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_lowered_referral_cap_applies_to_stored_rates() -> anyhow::Result<()> {
+    let mut ctx = init_naming().await?;
+    let set_ref_rate_inputs = NoteInputs::new([
+        Felt::new(2000),
+        Felt::new(0),
+        Felt::new(0),
+        Felt::new(0),
+        ctx.registrar_2.id().suffix(),
+        ctx.registrar_2.id().prefix().as_felt(),
+        Felt::new(0),
+        Felt::new(0),
+    ].to_vec())?;
+    let set_ref_rate_note = create_note_for_naming("set_referrer_rate".to_string(), set_ref_rate_inputs, ctx.owner.id(), ctx.naming.id(), NoteAssets::new(vec![])?).await?;
+    let parameters_note = create_set_parameters_note(ctx.owner.id(), ctx.naming.id(), parameters(ctx.one_year, 10, 30, 1000)).await?;
+    let refund_serial = Word::new([Felt::new(11), Felt::new(12), Felt::new(13), Felt::new(14)]);
+    let cost = FungibleAsset::new(ctx.fungible_asset.faucet_id(), 555)?;
+    let register_note = create_register_with_referrer_note(ctx.registrar_3.id(), ctx.naming.id(), ctx.registrar_2.id(), ctx.fungible_asset.faucet_id(), encode_domain("test"), 1, None, NoteAssets::new(vec![cost.into()])?, refund_serial).await?;
+    let mut chain = setup_parameters(&mut ctx, &[&set_ref_rate_note, &parameters_note, &register_note]).await?;
+
+    execute_note(&mut chain, set_ref_rate_note.id(), &mut ctx.naming).await?;
+    execute_note(&mut chain, parameters_note.id(), &mut ctx.naming).await?;
+
+    let result = execute_note(&mut chain, register_note.id(), &mut ctx.naming).await;
+    assert!(result.is_err(), "Expected register under a referrer rate above the lowered cap to fail, but it succeeded");
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_set_parameters_full_referral_cap_fails() -> anyhow::Result<()> {
+    let mut ctx = init_naming().await?;
+    let parameters_note = create_set_parameters_note(ctx.owner.id(), ctx.naming.id(), parameters(ctx.one_year, 10, 30, 10000)).await?;
+    let mut chain = setup_parameters(&mut ctx, &[&parameters_note]).await?;
+
+    let result = execute_note(&mut chain, parameters_note.id(), &mut ctx.naming).await;
+    assert!(result.is_err(), "Expected set_parameters with a 100% referral cap to fail, but it succeeded");
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_set_parameters_by_not_owner() -> anyhow::Result<()> {
+    let mut ctx = init_naming().await?;
+    let parameters_note = create_set_parameters_note(ctx.registrar_1.id(), ctx.naming.id(), parameters(ctx.one_year, 20, 30, 2500)).await?;
+    let mut chain = setup_parameters(&mut ctx, &[&parameters_note]).await?;
+
+    let result = execute_note(&mut chain, parameters_note.id(), &mut ctx.naming).await;
+    assert!(result.is_err(), "Expected set_parameters from non-owner to fail, but it succeeded");
+    Ok(())
+}
@@ -0,0 +1,137 @@
+mod test_utils;
+
+use miden_client::{asset::FungibleAsset, note::{Note, NoteAssets, NoteInputs, NoteType}};
+use miden_crypto::{Felt, Word};
+use miden_testing::MockChain;
+use midenname_contracts::{domain::encode_domain, notes::{create_register_note, create_set_grace_period_note, create_set_premium_note}, quote::{calculate_premium, quote_premium}};
+use test_utils::{init_naming, TestingContext};
+
+use crate::test_utils::{add_note_to_builder, advance_to_timestamp, create_note_for_naming, create_p2id_note_exact, execute_naming_fpi_call, execute_note, execute_note_with_expected_outputs, execute_notes_and_build_chain, words_to_stack};
+
+const GRACE_PERIOD: u32 = 300;
+const START_PREMIUM: u32 = 10000;
+const DECAY_WINDOW: u32 = 1000;
+const REBUY_PAYMENT: u64 = 555 + START_PREMIUM as u64;
+
+struct PremiumNotes {
+    rebuy_note: Note,
+    clear_note: Note,
+}
+
+// Sets the grace period and premium, then registers "test" for one year from registrar_1.
+// Returns a register note from registrar_2 paying the base price plus the full start premium.
+async fn setup_premium(ctx: &mut TestingContext) -> anyhow::Result<(MockChain, PremiumNotes)> {
+    let refund_serial = Word::new([Felt::new(11), Felt::new(12), Felt::new(13), Felt::new(14)]);
+    let domain_word = encode_domain("test");
+    let token = ctx.fungible_asset.faucet_id();
+
+    let grace_note = create_set_grace_period_note(ctx.owner.id(), ctx.naming.id(), GRACE_PERIOD).await?;
+    let premium_note = create_set_premium_note(ctx.owner.id(), ctx.naming.id(), token, START_PREMIUM, DECAY_WINDOW).await?;
+    let register_note = create_register_note(ctx.registrar_1.id(), ctx.naming.id(), token, domain_word, 1, None, NoteAssets::new(vec![FungibleAsset::new(token, 555)?.into()])?, refund_serial).await?;
+    let rebuy_note = create_register_note(ctx.registrar_2.id(), ctx.naming.id(), token, domain_word, 1, None, NoteAssets::new(vec![FungibleAsset::new(token, REBUY_PAYMENT)?.into()])?, refund_serial).await?;
+    let clear_note = create_note_for_naming("clear_expired_domain".to_string(), NoteInputs::new(domain_word.to_vec())?, ctx.registrar_3.id(), ctx.naming.id(), NoteAssets::new(vec![])?).await?;
+    for note in [&grace_note, &premium_note, &register_note, &rebuy_note, &clear_note] {
+        add_note_to_builder(&mut ctx.builder, note.clone())?;
+    }
+
+    let builder = std::mem::replace(&mut ctx.builder, MockChain::builder());
+    let chain = execute_notes_and_build_chain(builder, &[ctx.initialize_note.id(), ctx.set_prices_note.id(), grace_note.id(), premium_note.id(), register_note.id()], &mut ctx.naming).await?;
+
+    assert_eq!(ctx.naming.storage().get_item(18)?.first().unwrap().as_int(), DECAY_WINDOW as u64);
+    Ok((chain, PremiumNotes { rebuy_note, clear_note }))
+}
+
+fn get_release_time(ctx: &TestingContext) -> anyhow::Result<u64> {
+    let expiry = ctx.naming.storage().get_map_item(12, encode_domain("test"))?.first().unwrap().as_int();
+    Ok(expiry + GRACE_PERIOD as u64)
+}
+
+// Registers "test" again from registrar_2 and checks the change refunded for `premium`
+async fn rebuy_with_premium(ctx: &mut TestingContext, chain: &mut MockChain, rebuy_note: &Note, premium: u64) -> anyhow::Result<()> {
+    let refund_serial = Word::new([Felt::new(11), Felt::new(12), Felt::new(13), Felt::new(14)]);
+    let change = FungibleAsset::new(ctx.fungible_asset.faucet_id(), REBUY_PAYMENT - 555 - premium)?;
+    let refund_note = create_p2id_note_exact(ctx.naming.id(), ctx.registrar_2.id(), vec![change.into()], NoteType::Public, Felt::new(0), refund_serial)?;
+
+    let executed_tx = execute_note_with_expected_outputs(chain, rebuy_note.id(), &mut ctx.naming, vec![refund_note.clone()]).await?;
+    assert_eq!(executed_tx.output_notes().get_note(0).id(), refund_note.id());
+    Ok(())
+}
+
+#[test]
+fn test_calculate_premium() {
+    assert_eq!(calculate_premium(10000, 1000, 5000, 4999), 0); // Not released yet
+    assert_eq!(calculate_premium(10000, 1000, 5000, 5000), 10000);
+    assert_eq!(calculate_premium(10000, 1000, 5000, 5250), 7500);
+    assert_eq!(calculate_premium(10000, 1000, 5000, 5999), 10);
+    assert_eq!(calculate_premium(10000, 1000, 5000, 6000), 0);
+    assert_eq!(calculate_premium(10000, 0, 5000, 5000), 0);
+}
+
+#[tokio::test]
+async fn test_register_released_domain_with_premium() -> anyhow::Result<()> {
+    let mut ctx = init_naming().await?;
+    let (mut chain, notes) = setup_premium(&mut ctx).await?;
+    let domain_word = encode_domain("test");
+    let token = ctx.fungible_asset.faucet_id();
+
+    advance_to_timestamp(&mut chain, get_release_time(&ctx)? + DECAY_WINDOW as u64 / 2)?;
+    let timestamp = chain.latest_block_header().timestamp();
+    let premium = quote_premium(ctx.naming.storage(), domain_word, token, timestamp)?;
+    assert!(premium > 0 && premium <= START_PREMIUM as u64 / 2);
+
+    let token_word = Word::new([token.suffix(), token.prefix().as_felt(), Felt::new(0), Felt::new(0)]);
+    execute_naming_fpi_call(&chain, &ctx.fpi_reader, &ctx.naming, "quote_premium", &words_to_stack(&[domain_word, token_word]), &[Felt::new(premium)]).await?;
+
+    rebuy_with_premium(&mut ctx, &mut chain, &notes.rebuy_note, premium).await?;
+
+    let domain_owner_slot = ctx.naming.storage().get_map_item(5, domain_word)?;
+    assert_eq!(domain_owner_slot.first().unwrap().as_int(), ctx.registrar_2.id().suffix().as_int());
+    assert_eq!(domain_owner_slot.get(1).unwrap().as_int(), ctx.registrar_2.id().prefix().as_u64());
+
+    // Both registrations and the whole premium are protocol revenue
+    let total_revenue_slot = ctx.naming.storage().get_map_item(10, token_word)?;
+    assert_eq!(total_revenue_slot.first().unwrap().as_int(), 555 + 555 + premium);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_register_released_domain_after_premium_decayed() -> anyhow::Result<()> {
+    let mut ctx = init_naming().await?;
+    let (mut chain, notes) = setup_premium(&mut ctx).await?;
+
+    advance_to_timestamp(&mut chain, get_release_time(&ctx)? + DECAY_WINDOW as u64)?;
+    let timestamp = chain.latest_block_header().timestamp();
+    assert_eq!(quote_premium(ctx.naming.storage(), encode_domain("test"), ctx.fungible_asset.faucet_id(), timestamp)?, 0);
+
+    rebuy_with_premium(&mut ctx, &mut chain, &notes.rebuy_note, 0).await?;
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_clear_expired_domain_waits_for_premium() -> anyhow::Result<()> {
+    let mut ctx = init_naming().await?;
+    let (mut chain, notes) = setup_premium(&mut ctx).await?;
+    let release_time = get_release_time(&ctx)?;
+
+    // Clearing would reset the expiry and skip the premium
+    advance_to_timestamp(&mut chain, release_time + 1)?;
+    let result = execute_note(&mut chain, notes.clear_note.id(), &mut ctx.naming).await;
+    assert!(result.is_err(), "Expected clear while the premium decays to fail, but it succeeded");
+
+    advance_to_timestamp(&mut chain, release_time + DECAY_WINDOW as u64)?;
+    execute_note(&mut chain, notes.clear_note.id(), &mut ctx.naming).await?;
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_set_premium_by_not_owner() -> anyhow::Result<()> {
+    let mut ctx = init_naming().await?;
+    let premium_note = create_set_premium_note(ctx.registrar_1.id(), ctx.naming.id(), ctx.fungible_asset.faucet_id(), START_PREMIUM, DECAY_WINDOW).await?;
+    add_note_to_builder(&mut ctx.builder, premium_note.clone())?;
+    let mut chain = execute_notes_and_build_chain(ctx.builder, &[ctx.initialize_note.id(), ctx.set_prices_note.id()], &mut ctx.naming).await?;
+
+    let result = execute_note(&mut chain, premium_note.id(), &mut ctx.naming).await;
+
+    assert!(result.is_err(), "Expected set premium by not owner to fail, but it succeeded");
+    Ok(())
+}
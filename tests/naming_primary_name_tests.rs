@@ -0,0 +1,198 @@
+mod test_utils;
+
+use miden_client::{account::AccountId, asset::FungibleAsset, note::{Note, NoteAssets, NoteInputs}};
+use miden_crypto::{Felt, Word};
+use miden_testing::MockChain;
+use midenname_contracts::{domain::encode_domain, notes::{create_deactivate_domain_note, create_register_note, create_set_primary_name_note, create_set_resolution_target_note}};
+use test_utils::{init_naming, TestingContext};
+
+use crate::test_utils::{add_note_to_builder, create_note_for_naming, create_note_for_naming_with_custom_serial_num, execute_note, execute_notes_and_build_chain};
+
+fn account_id_word(id: AccountId) -> Word {
+    Word::new([id.suffix(), id.prefix().as_felt(), Felt::new(0), Felt::new(0)])
+}
+
+fn get_primary_name(ctx: &TestingContext, account: AccountId) -> anyhow::Result<Word> {
+    Ok(ctx.naming.storage().get_map_item(3, account_id_word(account))?)
+}
+
+async fn create_transfer_note(ctx: &TestingContext, name: &str, new_owner: AccountId) -> anyhow::Result<Note> {
+    let mut inputs = account_id_word(new_owner).to_vec();
+    inputs.extend(encode_domain(name).to_vec());
+    create_note_for_naming("transfer_domain".to_string(), NoteInputs::new(inputs)?, ctx.registrar_1.id(), ctx.naming.id(), NoteAssets::new(vec![])?).await
+}
+
+// Registers and activates "test" and "test2" for registrar_1, then makes "test" its primary name.
+// `extra_notes` are added to the chain without being executed
+async fn setup_primary_name(ctx: &mut TestingContext, extra_notes: &[&Note]) -> anyhow::Result<MockChain> {
+    let refund_serial = Word::new([Felt::new(11), Felt::new(12), Felt::new(13), Felt::new(14)]);
+    let token = ctx.fungible_asset.faucet_id();
+    let mut note_ids = vec![ctx.initialize_note.id(), ctx.set_prices_note.id()];
+
+    for (name, price) in [("test", 555), ("test2", 123)] {
+        let cost = FungibleAsset::new(token, price)?;
+        let register_note = create_register_note(ctx.registrar_1.id(), ctx.naming.id(), token, encode_domain(name), 1, None, NoteAssets::new(vec![cost.into()])?, refund_serial).await?;
+        let activate_note = create_note_for_naming("activate_domain".to_string(), NoteInputs::new(encode_domain(name).to_vec())?, ctx.registrar_1.id(), ctx.naming.id(), NoteAssets::new(vec![])?).await?;
+        add_note_to_builder(&mut ctx.builder, register_note.clone())?;
+        add_note_to_builder(&mut ctx.builder, activate_note.clone())?;
+        note_ids.extend([register_note.id(), activate_note.id()]);
+    }
+    let primary_note = create_set_primary_name_note(ctx.registrar_1.id(), ctx.naming.id(), encode_domain("test")).await?;
+    add_note_to_builder(&mut ctx.builder, primary_note.clone())?;
+    note_ids.push(primary_note.id());
+    for note in extra_notes {
+        add_note_to_builder(&mut ctx.builder, (*note).clone())?;
+    }
+
+    let builder = std::mem::replace(&mut ctx.builder, MockChain::builder());
+    let chain = execute_notes_and_build_chain(builder, &note_ids, &mut ctx.naming).await?;
+
+    assert_eq!(get_primary_name(ctx, ctx.registrar_1.id())?, encode_domain("test"));
+    Ok(chain)
+}
+
+#[tokio::test]
+async fn test_transfer_drops_primary_name() -> anyhow::Result<()> {
+    let mut ctx = init_naming().await?;
+    let transfer_note = create_transfer_note(&ctx, "test", ctx.registrar_2.id()).await?;
+    let mut chain = setup_primary_name(&mut ctx, &[&transfer_note]).await?;
+
+    execute_note(&mut chain, transfer_note.id(), &mut ctx.naming).await?;
+
+    assert_eq!(get_primary_name(&ctx, ctx.registrar_1.id())?, Word::default());
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_transfer_other_name_keeps_primary_name() -> anyhow::Result<()> {
+    let mut ctx = init_naming().await?;
+    let transfer_note = create_transfer_note(&ctx, "test2", ctx.registrar_2.id()).await?;
+    let mut chain = setup_primary_name(&mut ctx, &[&transfer_note]).await?;
+
+    execute_note(&mut chain, transfer_note.id(), &mut ctx.naming).await?;
+
+    assert_eq!(get_primary_name(&ctx, ctx.registrar_1.id())?, encode_domain("test"));
+    assert_eq!(ctx.naming.storage().get_map_item(4, encode_domain("test2"))?, Word::default());
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_change_primary_name() -> anyhow::Result<()> {
+    let mut ctx = init_naming().await?;
+    let primary_note = create_set_primary_name_note(ctx.registrar_1.id(), ctx.naming.id(), encode_domain("test2")).await?;
+    let mut chain = setup_primary_name(&mut ctx, &[&primary_note]).await?;
+
+    execute_note(&mut chain, primary_note.id(), &mut ctx.naming).await?;
+
+    assert_eq!(get_primary_name(&ctx, ctx.registrar_1.id())?, encode_domain("test2"));
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_set_primary_name_not_resolving_to_sender() -> anyhow::Result<()> {
+    let mut ctx = init_naming().await?;
+    let primary_note = create_set_primary_name_note(ctx.registrar_2.id(), ctx.naming.id(), encode_domain("test")).await?;
+    let mut chain = setup_primary_name(&mut ctx, &[&primary_note]).await?;
+
+    let result = execute_note(&mut chain, primary_note.id(), &mut ctx.naming).await;
+    assert!(result.is_err(), "Expected primary name resolving to another account to fail, but it succeeded");
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_set_primary_name_after_expiry() -> anyhow::Result<()> {
+    let mut ctx = init_naming().await?;
+    let primary_note = create_set_primary_name_note(ctx.registrar_1.id(), ctx.naming.id(), encode_domain("test2")).await?;
+    let mut chain = setup_primary_name(&mut ctx, &[&primary_note]).await?;
+    chain.prove_until_block(100)?;
+
+    let result = execute_note(&mut chain, primary_note.id(), &mut ctx.naming).await;
+    assert!(result.is_err(), "Expected primary name of an expired domain to fail, but it succeeded");
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_deactivate_primary_domain() -> anyhow::Result<()> {
+    let mut ctx = init_naming().await?;
+    let deactivate_note = create_deactivate_domain_note(ctx.registrar_1.id(), ctx.naming.id(), encode_domain("test")).await?;
+    let mut chain = setup_primary_name(&mut ctx, &[&deactivate_note]).await?;
+    let expiry = ctx.naming.storage().get_map_item(12, encode_domain("test"))?;
+
+    execute_note(&mut chain, deactivate_note.id(), &mut ctx.naming).await?;
+
+    assert_eq!(ctx.naming.storage().get_map_item(4, encode_domain("test"))?, Word::default());
+    assert_eq!(get_primary_name(&ctx, ctx.registrar_1.id())?, Word::default());
+
+    // Ownership and expiry are kept
+    assert_eq!(ctx.naming.storage().get_map_item(5, encode_domain("test"))?, account_id_word(ctx.registrar_1.id()));
+    assert_eq!(ctx.naming.storage().get_map_item(12, encode_domain("test"))?, expiry);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_deactivate_other_domain_keeps_primary_name() -> anyhow::Result<()> {
+    let mut ctx = init_naming().await?;
+    let deactivate_note = create_deactivate_domain_note(ctx.registrar_1.id(), ctx.naming.id(), encode_domain("test2")).await?;
+    let mut chain = setup_primary_name(&mut ctx, &[&deactivate_note]).await?;
+
+    execute_note(&mut chain, deactivate_note.id(), &mut ctx.naming).await?;
+
+    assert_eq!(ctx.naming.storage().get_map_item(4, encode_domain("test2"))?, Word::default());
+    assert_eq!(get_primary_name(&ctx, ctx.registrar_1.id())?, encode_domain("test"));
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_deactivate_domain_by_not_owner() -> anyhow::Result<()> {
+    let mut ctx = init_naming().await?;
+    let deactivate_note = create_deactivate_domain_note(ctx.registrar_2.id(), ctx.naming.id(), encode_domain("test")).await?;
+    let mut chain = setup_primary_name(&mut ctx, &[&deactivate_note]).await?;
+
+    let result = execute_note(&mut chain, deactivate_note.id(), &mut ctx.naming).await;
+    assert!(result.is_err(), "Expected deactivate from non-owner to fail, but it succeeded");
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_resolution_target_sets_primary_name_with_consent() -> anyhow::Result<()> {
+    let mut ctx = init_naming().await?;
+    let target_note = create_set_resolution_target_note(ctx.registrar_1.id(), ctx.naming.id(), encode_domain("test"), ctx.registrar_2.id()).await?;
+    let owner_primary_note = create_set_primary_name_note(ctx.registrar_1.id(), ctx.naming.id(), encode_domain("test")).await?;
+    let target_primary_note = create_set_primary_name_note(ctx.registrar_2.id(), ctx.naming.id(), encode_domain("test")).await?;
+    // Distinct serial, setup already consumed an activate note for the same domain
+    let activate_serial = Word::new([Felt::new(21), Felt::new(22), Felt::new(23), Felt::new(24)]);
+    let activate_note = create_note_for_naming_with_custom_serial_num("activate_domain".to_string(), NoteInputs::new(encode_domain("test").to_vec())?, ctx.registrar_1.id(), ctx.naming.id(), NoteAssets::new(vec![])?, activate_serial).await?;
+    let mut chain = setup_primary_name(&mut ctx, &[&target_note, &owner_primary_note, &target_primary_note, &activate_note]).await?;
+
+    execute_note(&mut chain, target_note.id(), &mut ctx.naming).await?;
+
+    // Forward mapping moves to the target, the owner loses the primary name
+    assert_eq!(ctx.naming.storage().get_map_item(4, encode_domain("test"))?, account_id_word(ctx.registrar_2.id()));
+    assert_eq!(ctx.naming.storage().get_map_item(5, encode_domain("test"))?, account_id_word(ctx.registrar_1.id()));
+    assert_eq!(get_primary_name(&ctx, ctx.registrar_1.id())?, Word::default());
+    assert_eq!(get_primary_name(&ctx, ctx.registrar_2.id())?, Word::default());
+
+    let result = execute_note(&mut chain, owner_primary_note.id(), &mut ctx.naming).await;
+    assert!(result.is_err(), "Expected owner primary name for a name resolving elsewhere to fail, but it succeeded");
+
+    // Target consents with its own note
+    execute_note(&mut chain, target_primary_note.id(), &mut ctx.naming).await?;
+    assert_eq!(get_primary_name(&ctx, ctx.registrar_2.id())?, encode_domain("test"));
+
+    // Pointing the name back at the owner drops the reverse record of the target
+    execute_note(&mut chain, activate_note.id(), &mut ctx.naming).await?;
+    assert_eq!(ctx.naming.storage().get_map_item(4, encode_domain("test"))?, account_id_word(ctx.registrar_1.id()));
+    assert_eq!(get_primary_name(&ctx, ctx.registrar_2.id())?, Word::default());
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_set_resolution_target_by_not_owner() -> anyhow::Result<()> {
+    let mut ctx = init_naming().await?;
+    let target_note = create_set_resolution_target_note(ctx.registrar_2.id(), ctx.naming.id(), encode_domain("test"), ctx.registrar_2.id()).await?;
+    let mut chain = setup_primary_name(&mut ctx, &[&target_note]).await?;
+
+    let result = execute_note(&mut chain, target_note.id(), &mut ctx.naming).await;
+    assert!(result.is_err(), "Expected set_resolution_target from non-owner to fail, but it succeeded");
+    Ok(())
+}
@@ -1,23 +1,15 @@
 mod test_utils;
 
-use std::any::Any;
-
-use miden_client::{asset::FungibleAsset, note::{NoteAssets, NoteExecutionHint, NoteInputs, NoteTag, NoteType}, transaction::OutputNote};
-use miden_crypto::{Felt, Word, rand::RpoRandomCoin};
-use miden_lib::note::create_p2id_note;
-use midenname_contracts::domain::{encode_domain, encode_domain_as_felts, unsafe_encode_domain};
-use rand::SeedableRng;
-use rand_chacha::ChaCha20Rng;
+use miden_client::{asset::FungibleAsset, note::{NoteAssets, NoteInputs, NoteType}, transaction::OutputNote};
+use miden_crypto::{Felt, Word};
+use midenname_contracts::{domain::encode_domain_as_felts, notes::{create_claim_protocol_revenue_note, create_withdraw_assets_note}};
 use test_utils::init_naming;
 
-use crate::test_utils::{add_note_to_builder, create_note_for_naming, create_p2id_note_exact, execute_note, execute_notes_and_build_chain};
+use crate::test_utils::{add_note_to_builder, create_note_for_naming, create_p2id_note_exact, execute_note, execute_note_with_expected_outputs, execute_notes_and_build_chain};
 
 #[tokio::test]
-#[ignore = "not implemented"]
 async fn test_claim_protocol_revenue() -> anyhow::Result<()> {
     let mut ctx = init_naming().await?;
-    println!("\nOwner prefix: {}, suffix: {}", ctx.owner.id().prefix().to_string(), ctx.owner.id().suffix().to_string());
-    println!("Naming prefix: {}, suffix: {}", ctx.naming.id().prefix().to_string(), ctx.naming.id().suffix().to_string());
     // Register domain to increase protocol revenue
     let domain = encode_domain_as_felts("test".to_string());
     let register_note_inputs = NoteInputs::new([
@@ -38,32 +30,231 @@ async fn test_claim_protocol_revenue() -> anyhow::Result<()> {
     let cost = FungibleAsset::new(ctx.fungible_asset.faucet_id(), 555)?;
     let register_asset = NoteAssets::new(vec![cost.into()])?;
     let register_note = create_note_for_naming("register_name".to_string(), register_note_inputs, ctx.registrar_1.id(), ctx.naming.id(), register_asset.clone()).await?;
+    add_note_to_builder(&mut ctx.builder, register_note.clone())?;
+
+    let payout_serial = Word::new([Felt::new(27), Felt::new(27), Felt::new(27), Felt::new(27)]);
+    let withdraw_note = create_claim_protocol_revenue_note(ctx.owner.id(), ctx.naming.id(), ctx.fungible_asset.faucet_id(), payout_serial).await?;
+    add_note_to_builder(&mut ctx.builder, withdraw_note.clone())?;
     
+    let mut chain = execute_notes_and_build_chain(ctx.builder, &[ctx.initialize_note.id(), ctx.set_prices_note.id(), register_note.id()], &mut ctx.naming).await?;
+
+    let p2id_note = create_p2id_note_exact(ctx.naming.id(), ctx.owner.id(), vec![cost.into()], NoteType::Public, Felt::new(0), payout_serial)?;
+    let executed_tx = execute_note_with_expected_outputs(&mut chain, withdraw_note.id(), &mut ctx.naming, vec![p2id_note.clone()]).await?;
+
+    assert_eq!(executed_tx.output_notes().num_notes(), 1);
+    assert_eq!(executed_tx.output_notes().get_note(0).id(), p2id_note.id());
+
+    let token_key = Word::new([Felt::new(ctx.fungible_asset.faucet_id().suffix().as_int()), Felt::new(ctx.fungible_asset.faucet_id().prefix().as_u64()), Felt::new(0), Felt::new(0)]);
+    let total_revenue_slot = ctx.naming.storage().get_map_item(10, token_key)?;
+    let claimed_revenue_slot = ctx.naming.storage().get_map_item(11, token_key)?;
+    assert_eq!(total_revenue_slot.first().unwrap().as_int(), 555);
+    assert_eq!(claimed_revenue_slot.first().unwrap().as_int(), 555);
+
+    assert_eq!(ctx.naming.vault().get_balance(ctx.fungible_asset.faucet_id())?, 0);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_claim_protocol_revenue_twice() -> anyhow::Result<()> {
+    let mut ctx = init_naming().await?;
+    let domain = encode_domain_as_felts("test".to_string());
+    let register_note_inputs = NoteInputs::new([
+        Felt::new(ctx.fungible_asset.faucet_id().suffix().as_int()),
+        ctx.fungible_asset.faucet_id().prefix().as_felt(),
+        Felt::new(0),
+        Felt::new(0),
+        domain[0],
+        domain[1],
+        domain[2],
+        domain[3],
+        Felt::new(1), // register length
+        Felt::new(0),
+        Felt::new(0),
+        Felt::new(0),
+    ].to_vec())?;
+    
+    let cost = FungibleAsset::new(ctx.fungible_asset.faucet_id(), 555)?;
+    let register_asset = NoteAssets::new(vec![cost.into()])?;
+    let register_note = create_note_for_naming("register_name".to_string(), register_note_inputs, ctx.registrar_1.id(), ctx.naming.id(), register_asset.clone()).await?;
     add_note_to_builder(&mut ctx.builder, register_note.clone())?;
 
-    let p2id_note = create_p2id_note_exact(ctx.naming.id(), ctx.owner.id(), vec![cost.into()], NoteType::Public, Felt::new(27), Word::default())?;
-    let p2id_recipient = p2id_note.recipient().digest();
+    let payout_serial = Word::new([Felt::new(27), Felt::new(27), Felt::new(27), Felt::new(27)]);
+    let payout_serial_2 = Word::new([Felt::new(28), Felt::new(28), Felt::new(28), Felt::new(28)]);
+    let withdraw_note = create_claim_protocol_revenue_note(ctx.owner.id(), ctx.naming.id(), ctx.fungible_asset.faucet_id(), payout_serial).await?;
+    let withdraw_note_2 = create_claim_protocol_revenue_note(ctx.owner.id(), ctx.naming.id(), ctx.fungible_asset.faucet_id(), payout_serial_2).await?;
+    add_note_to_builder(&mut ctx.builder, withdraw_note.clone())?;
+    add_note_to_builder(&mut ctx.builder, withdraw_note_2.clone())?;
     
-    let withdraw_note_inputs = NoteInputs::new([
-        p2id_recipient[0],
-        p2id_recipient[1],
-        p2id_recipient[2],
-        p2id_recipient[3],
-        NoteExecutionHint::Always.into(),
-        NoteType::Public.into(),
-        Felt::new(27),
-        NoteTag::from_account_id(ctx.naming.id()).into(),
+    let mut chain = execute_notes_and_build_chain(ctx.builder, &[ctx.initialize_note.id(), ctx.set_prices_note.id(), register_note.id()], &mut ctx.naming).await?;
+
+    let p2id_note = create_p2id_note_exact(ctx.naming.id(), ctx.owner.id(), vec![cost.into()], NoteType::Public, Felt::new(0), payout_serial)?;
+    execute_note_with_expected_outputs(&mut chain, withdraw_note.id(), &mut ctx.naming, vec![p2id_note]).await?;
+
+    let result = execute_note(&mut chain, withdraw_note_2.id(), &mut ctx.naming).await;
+    assert!(result.is_err(), "Expected second claim to fail, but it succeeded");
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_claim_protocol_revenue_by_not_owner() -> anyhow::Result<()> {
+    let mut ctx = init_naming().await?;
+    let domain = encode_domain_as_felts("test".to_string());
+    let register_note_inputs = NoteInputs::new([
         Felt::new(ctx.fungible_asset.faucet_id().suffix().as_int()),
-        Felt::new(ctx.fungible_asset.faucet_id().prefix().as_u64()),
+        ctx.fungible_asset.faucet_id().prefix().as_felt(),
+        Felt::new(0),
+        Felt::new(0),
+        domain[0],
+        domain[1],
+        domain[2],
+        domain[3],
+        Felt::new(1), // register length
+        Felt::new(0),
         Felt::new(0),
         Felt::new(0),
     ].to_vec())?;
-    let withdraw_note = create_note_for_naming("claim_protocol_revenue".to_string(), withdraw_note_inputs.clone(), ctx.owner.id(), ctx.naming.id(), NoteAssets::new(vec![])?).await?;
+    
+    let cost = FungibleAsset::new(ctx.fungible_asset.faucet_id(), 555)?;
+    let register_asset = NoteAssets::new(vec![cost.into()])?;
+    let register_note = create_note_for_naming("register_name".to_string(), register_note_inputs, ctx.registrar_1.id(), ctx.naming.id(), register_asset.clone()).await?;
+    add_note_to_builder(&mut ctx.builder, register_note.clone())?;
+
+    let payout_serial = Word::new([Felt::new(27), Felt::new(27), Felt::new(27), Felt::new(27)]);
+    let withdraw_note = create_claim_protocol_revenue_note(ctx.registrar_1.id(), ctx.naming.id(), ctx.fungible_asset.faucet_id(), payout_serial).await?;
     add_note_to_builder(&mut ctx.builder, withdraw_note.clone())?;
     
     let mut chain = execute_notes_and_build_chain(ctx.builder, &[ctx.initialize_note.id(), ctx.set_prices_note.id(), register_note.id()], &mut ctx.naming).await?;
 
-    execute_note(&mut chain, withdraw_note.id(), &mut ctx.naming).await?;
-    // check balances
+    let result = execute_note(&mut chain, withdraw_note.id(), &mut ctx.naming).await;
+    assert!(result.is_err(), "Expected claim by non-owner to fail, but it succeeded");
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_withdraw_stray_assets() -> anyhow::Result<()> {
+    let mut ctx = init_naming().await?;
+    let domain = encode_domain_as_felts("test".to_string());
+    let register_note_inputs = NoteInputs::new([
+        Felt::new(ctx.fungible_asset.faucet_id().suffix().as_int()),
+        ctx.fungible_asset.faucet_id().prefix().as_felt(),
+        Felt::new(0),
+        Felt::new(0),
+        domain[0],
+        domain[1],
+        domain[2],
+        domain[3],
+        Felt::new(1), // register length
+        Felt::new(0),
+        Felt::new(0),
+        Felt::new(0),
+    ].to_vec())?;
+    
+    let cost = FungibleAsset::new(ctx.fungible_asset.faucet_id(), 555)?;
+    let register_note = create_note_for_naming("register_name".to_string(), register_note_inputs, ctx.registrar_1.id(), ctx.naming.id(), NoteAssets::new(vec![cost.into()])?).await?;
+    add_note_to_builder(&mut ctx.builder, register_note.clone())?;
+
+    // Assets sent to the registry outside of any registration
+    let stray = FungibleAsset::new(ctx.fungible_asset.faucet_id(), 300)?;
+    let stray_note = create_p2id_note_exact(ctx.registrar_1.id(), ctx.naming.id(), vec![stray.into()], NoteType::Public, Felt::new(0), Word::default())?;
+    add_note_to_builder(&mut ctx.builder, stray_note.clone())?;
+
+    let payout_serial = Word::new([Felt::new(27), Felt::new(27), Felt::new(27), Felt::new(27)]);
+    let withdraw_note = create_withdraw_assets_note(ctx.owner.id(), ctx.naming.id(), ctx.fungible_asset.faucet_id(), 300, payout_serial).await?;
+    add_note_to_builder(&mut ctx.builder, withdraw_note.clone())?;
+
+    let mut chain = execute_notes_and_build_chain(ctx.builder, &[ctx.initialize_note.id(), ctx.set_prices_note.id(), register_note.id(), stray_note.id()], &mut ctx.naming).await?;
+
+    let p2id_note = create_p2id_note_exact(ctx.naming.id(), ctx.owner.id(), vec![stray.into()], NoteType::Public, Felt::new(0), payout_serial)?;
+    let executed_tx = execute_note_with_expected_outputs(&mut chain, withdraw_note.id(), &mut ctx.naming, vec![p2id_note.clone()]).await?;
+
+    assert_eq!(executed_tx.output_notes().get_note(0).id(), p2id_note.id());
+    // Protocol revenue stays in the vault
+    assert_eq!(ctx.naming.vault().get_balance(ctx.fungible_asset.faucet_id())?, 555);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_withdraw_assets_owed_as_revenue() -> anyhow::Result<()> {
+    let mut ctx = init_naming().await?;
+    let domain = encode_domain_as_felts("test".to_string());
+    let register_note_inputs = NoteInputs::new([
+        Felt::new(ctx.fungible_asset.faucet_id().suffix().as_int()),
+        ctx.fungible_asset.faucet_id().prefix().as_felt(),
+        Felt::new(0),
+        Felt::new(0),
+        domain[0],
+        domain[1],
+        domain[2],
+        domain[3],
+        Felt::new(1), // register length
+        Felt::new(0),
+        Felt::new(0),
+        Felt::new(0),
+    ].to_vec())?;
+    
+    let cost = FungibleAsset::new(ctx.fungible_asset.faucet_id(), 555)?;
+    let register_note = create_note_for_naming("register_name".to_string(), register_note_inputs, ctx.registrar_1.id(), ctx.naming.id(), NoteAssets::new(vec![cost.into()])?).await?;
+    add_note_to_builder(&mut ctx.builder, register_note.clone())?;
+
+    let stray = FungibleAsset::new(ctx.fungible_asset.faucet_id(), 300)?;
+    let stray_note = create_p2id_note_exact(ctx.registrar_1.id(), ctx.naming.id(), vec![stray.into()], NoteType::Public, Felt::new(0), Word::default())?;
+    add_note_to_builder(&mut ctx.builder, stray_note.clone())?;
+
+    let payout_serial = Word::new([Felt::new(27), Felt::new(27), Felt::new(27), Felt::new(27)]);
+    let withdraw_note = create_withdraw_assets_note(ctx.owner.id(), ctx.naming.id(), ctx.fungible_asset.faucet_id(), 301, payout_serial).await?;
+    add_note_to_builder(&mut ctx.builder, withdraw_note.clone())?;
+
+    let mut chain = execute_notes_and_build_chain(ctx.builder, &[ctx.initialize_note.id(), ctx.set_prices_note.id(), register_note.id(), stray_note.id()], &mut ctx.naming).await?;
+
+    let result = execute_note(&mut chain, withdraw_note.id(), &mut ctx.naming).await;
+    assert!(result.is_err(), "Expected withdrawing protocol revenue to fail, but it succeeded");
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_withdraw_assets_by_not_owner() -> anyhow::Result<()> {
+    let mut ctx = init_naming().await?;
+
+    let stray = FungibleAsset::new(ctx.fungible_asset.faucet_id(), 300)?;
+    let stray_note = create_p2id_note_exact(ctx.registrar_1.id(), ctx.naming.id(), vec![stray.into()], NoteType::Public, Felt::new(0), Word::default())?;
+    add_note_to_builder(&mut ctx.builder, stray_note.clone())?;
+
+    let payout_serial = Word::new([Felt::new(27), Felt::new(27), Felt::new(27), Felt::new(27)]);
+    let withdraw_note = create_withdraw_assets_note(ctx.registrar_1.id(), ctx.naming.id(), ctx.fungible_asset.faucet_id(), 300, payout_serial).await?;
+    add_note_to_builder(&mut ctx.builder, withdraw_note.clone())?;
+
+    let mut chain = execute_notes_and_build_chain(ctx.builder, &[ctx.initialize_note.id(), ctx.set_prices_note.id(), stray_note.id()], &mut ctx.naming).await?;
+
+    let result = execute_note(&mut chain, withdraw_note.id(), &mut ctx.naming).await;
+    assert!(result.is_err(), "Expected withdraw by non-owner to fail, but it succeeded");
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_withdraw_assets_batched_behind_owner_note() -> anyhow::Result<()> {
+    let mut ctx = init_naming().await?;
+
+    let stray = FungibleAsset::new(ctx.fungible_asset.faucet_id(), 300)?;
+    let stray_note = create_p2id_note_exact(ctx.registrar_1.id(), ctx.naming.id(), vec![stray.into()], NoteType::Public, Felt::new(0), Word::default())?;
+    add_note_to_builder(&mut ctx.builder, stray_note.clone())?;
+
+    // Any note of the owner, consumed first in the same transaction
+    let owner_serial = Word::new([Felt::new(28), Felt::new(28), Felt::new(28), Felt::new(28)]);
+    let owner_note = create_p2id_note_exact(ctx.owner.id(), ctx.naming.id(), vec![], NoteType::Public, Felt::new(0), owner_serial)?;
+    add_note_to_builder(&mut ctx.builder, owner_note.clone())?;
+
+    let payout_serial = Word::new([Felt::new(27), Felt::new(27), Felt::new(27), Felt::new(27)]);
+    let withdraw_note = create_withdraw_assets_note(ctx.registrar_1.id(), ctx.naming.id(), ctx.fungible_asset.faucet_id(), 300, payout_serial).await?;
+    add_note_to_builder(&mut ctx.builder, withdraw_note.clone())?;
+
+    let chain = execute_notes_and_build_chain(ctx.builder, &[ctx.initialize_note.id(), ctx.set_prices_note.id(), stray_note.id()], &mut ctx.naming).await?;
+
+    let p2id_note = create_p2id_note_exact(ctx.naming.id(), ctx.registrar_1.id(), vec![stray.into()], NoteType::Public, Felt::new(0), payout_serial)?;
+    let tx_ctx = chain.build_tx_context(ctx.naming.id(), &[owner_note.id(), withdraw_note.id()], &[])?
+        .extend_expected_output_notes(vec![OutputNote::Full(p2id_note)])
+        .build()?;
+    let result = tx_ctx.execute().await;
+
+    assert!(result.is_err(), "Expected withdraw batched behind an owner note to fail, but it succeeded");
     Ok(())
-}
\ No newline at end of file
+}
@@ -1,11 +1,12 @@
 mod test_utils;
 
-use miden_client::{asset::FungibleAsset, note::{NoteAssets, NoteInputs}};
+use miden_client::{account::AccountId, asset::FungibleAsset, testing::account_id::ACCOUNT_ID_PUBLIC_FUNGIBLE_FAUCET_2, note::{Note, NoteAssets, NoteInputs, NoteType}};
 use miden_crypto::{Felt, Word};
-use midenname_contracts::domain::{encode_domain, encode_domain_as_felts, unsafe_encode_domain};
-use test_utils::init_naming;
+use miden_testing::MockChain;
+use midenname_contracts::{domain::{encode_domain, encode_domain_as_felts, unsafe_encode_domain}, notes::{create_claim_referral_earnings_note, create_register_with_referrer_note, create_set_prices_note, create_withdraw_assets_note}, storage::referrer_revenue_key};
+use test_utils::{init_naming, TestingContext};
 
-use crate::test_utils::{add_note_to_builder, create_note_for_naming, execute_note, execute_notes_and_build_chain, get_test_prices, create_note_for_naming_with_custom_serial_num};
+use crate::test_utils::{add_note_to_builder, create_note_for_naming, create_p2id_note_exact, execute_note, execute_note_with_expected_outputs, execute_notes_and_build_chain, get_test_prices, get_test_price_entries, create_note_for_naming_with_custom_serial_num};
 #[tokio::test]
 async fn test_naming_register_under_referrer() -> anyhow::Result<()> {
     let mut ctx = init_naming().await?;
@@ -75,7 +76,7 @@ async fn test_naming_register_under_referrer() -> anyhow::Result<()> {
 
     // Referrer values
 
-    let referrer_slot = ctx.naming.storage().get_map_item(7, Word::new([Felt::new(ctx.registrar_2.id().suffix().as_int()), Felt::new(ctx.registrar_2.id().prefix().as_u64()), Felt::new(0), Felt::new(0)]))?;
+    let referrer_slot = ctx.naming.storage().get_map_item(7, referrer_revenue_key(ctx.registrar_2.id(), ctx.fungible_asset.faucet_id()))?;
     assert_eq!(referrer_slot.get(0).unwrap().as_int(), 111);
     Ok(())
 }
@@ -159,7 +160,244 @@ async fn test_naming_referrer_revenue_accumulation() -> anyhow::Result<()> {
 
     // Referrer values
 
-    let referrer_slot = ctx.naming.storage().get_map_item(7, Word::new([Felt::new(ctx.registrar_2.id().suffix().as_int()), Felt::new(ctx.registrar_2.id().prefix().as_u64()), Felt::new(0), Felt::new(0)]))?;
+    let referrer_slot = ctx.naming.storage().get_map_item(7, referrer_revenue_key(ctx.registrar_2.id(), ctx.fungible_asset.faucet_id()))?;
     assert_eq!(referrer_slot.get(0).unwrap().as_int(), 135);
     Ok(())
-}
\ No newline at end of file
+}
+
+// Sets registrar_2 as a 20% referrer, registers "test" under it and returns the chain after registration
+async fn setup_referral_revenue(ctx: &mut TestingContext, extra_notes: &[Note]) -> anyhow::Result<MockChain> {
+    let set_ref_rate_inputs = NoteInputs::new([
+        Felt::new(2000),
+        Felt::new(0),
+        Felt::new(0),
+        Felt::new(0),
+        Felt::new(ctx.registrar_2.id().suffix().as_int()),
+        Felt::new(ctx.registrar_2.id().prefix().as_u64()),
+        Felt::new(0),
+        Felt::new(0),
+    ].to_vec())?;
+
+    let set_ref_rate_note = create_note_for_naming("set_referrer_rate".to_string(), set_ref_rate_inputs, ctx.owner.id(), ctx.naming.id(), NoteAssets::new(vec![])?).await?;
+    add_note_to_builder(&mut ctx.builder, set_ref_rate_note.clone())?;
+
+    let domain = encode_domain_as_felts("test".to_string());
+    let register_note_inputs = NoteInputs::new([
+        Felt::new(ctx.registrar_2.id().suffix().as_int()),
+        Felt::new(ctx.registrar_2.id().prefix().as_u64()),
+        Felt::new(0),
+        Felt::new(0),
+        Felt::new(ctx.fungible_asset.faucet_id().suffix().as_int()),
+        ctx.fungible_asset.faucet_id().prefix().as_felt(),
+        Felt::new(0),
+        Felt::new(0),
+        domain[0],
+        domain[1],
+        domain[2],
+        domain[3],
+        Felt::new(1), // register length
+        Felt::new(0),
+        Felt::new(0),
+        Felt::new(0),
+    ].to_vec())?;
+
+    let cost = FungibleAsset::new(ctx.fungible_asset.faucet_id(), 555)?;
+    let register_asset = NoteAssets::new(vec![cost.into()])?;
+    let register_note = create_note_for_naming("register_with_referrer".to_string(), register_note_inputs, ctx.registrar_1.id(), ctx.naming.id(), register_asset).await?;
+    add_note_to_builder(&mut ctx.builder, register_note.clone())?;
+
+    for note in extra_notes {
+        add_note_to_builder(&mut ctx.builder, note.clone())?;
+    }
+
+    let builder = std::mem::replace(&mut ctx.builder, MockChain::builder());
+    execute_notes_and_build_chain(builder, &[ctx.initialize_note.id(), ctx.set_prices_note.id(), set_ref_rate_note.id(), register_note.id()], &mut ctx.naming).await
+}
+
+#[tokio::test]
+async fn test_claim_referral_earnings() -> anyhow::Result<()> {
+    let mut ctx = init_naming().await?;
+    let payout_serial = Word::new([Felt::new(7), Felt::new(7), Felt::new(7), Felt::new(7)]);
+
+    let claim_note = create_claim_referral_earnings_note(ctx.registrar_2.id(), ctx.naming.id(), ctx.fungible_asset.faucet_id(), 111, payout_serial).await?;
+    let mut chain = setup_referral_revenue(&mut ctx, std::slice::from_ref(&claim_note)).await?;
+
+    let payout = FungibleAsset::new(ctx.fungible_asset.faucet_id(), 111)?;
+    let expected_note = create_p2id_note_exact(ctx.naming.id(), ctx.registrar_2.id(), vec![payout.into()], NoteType::Public, Felt::new(0), payout_serial)?;
+
+    let executed_tx = execute_note_with_expected_outputs(&mut chain, claim_note.id(), &mut ctx.naming, vec![expected_note.clone()]).await?;
+
+    assert_eq!(executed_tx.output_notes().num_notes(), 1);
+    assert_eq!(executed_tx.output_notes().get_note(0).id(), expected_note.id());
+
+    let claimed_slot = ctx.naming.storage().get_map_item(8, referrer_revenue_key(ctx.registrar_2.id(), ctx.fungible_asset.faucet_id()))?;
+    assert_eq!(claimed_slot.first().unwrap().as_int(), 111);
+
+    assert_eq!(ctx.naming.vault().get_balance(ctx.fungible_asset.faucet_id())?, 444);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_claim_referral_earnings_more_than_earned() -> anyhow::Result<()> {
+    let mut ctx = init_naming().await?;
+    let payout_serial = Word::new([Felt::new(7), Felt::new(7), Felt::new(7), Felt::new(7)]);
+
+    let claim_note = create_claim_referral_earnings_note(ctx.registrar_2.id(), ctx.naming.id(), ctx.fungible_asset.faucet_id(), 112, payout_serial).await?;
+    let mut chain = setup_referral_revenue(&mut ctx, std::slice::from_ref(&claim_note)).await?;
+
+    let result = execute_note(&mut chain, claim_note.id(), &mut ctx.naming).await;
+
+    assert!(result.is_err(), "Expected over-claim to fail, but it succeeded");
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_claim_referral_earnings_twice() -> anyhow::Result<()> {
+    let mut ctx = init_naming().await?;
+    let payout_serial = Word::new([Felt::new(7), Felt::new(7), Felt::new(7), Felt::new(7)]);
+    let payout_serial_2 = Word::new([Felt::new(8), Felt::new(8), Felt::new(8), Felt::new(8)]);
+
+    let claim_note = create_claim_referral_earnings_note(ctx.registrar_2.id(), ctx.naming.id(), ctx.fungible_asset.faucet_id(), 111, payout_serial).await?;
+    let claim_note_2 = create_claim_referral_earnings_note(ctx.registrar_2.id(), ctx.naming.id(), ctx.fungible_asset.faucet_id(), 111, payout_serial_2).await?;
+    let mut chain = setup_referral_revenue(&mut ctx, &[claim_note.clone(), claim_note_2.clone()]).await?;
+
+    let payout = FungibleAsset::new(ctx.fungible_asset.faucet_id(), 111)?;
+    let expected_note = create_p2id_note_exact(ctx.naming.id(), ctx.registrar_2.id(), vec![payout.into()], NoteType::Public, Felt::new(0), payout_serial)?;
+    execute_note_with_expected_outputs(&mut chain, claim_note.id(), &mut ctx.naming, vec![expected_note]).await?;
+
+    let result = execute_note(&mut chain, claim_note_2.id(), &mut ctx.naming).await;
+
+    assert!(result.is_err(), "Expected second claim to fail, but it succeeded");
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_claim_referral_earnings_not_referrer() -> anyhow::Result<()> {
+    let mut ctx = init_naming().await?;
+    let payout_serial = Word::new([Felt::new(7), Felt::new(7), Felt::new(7), Felt::new(7)]);
+
+    let claim_note = create_claim_referral_earnings_note(ctx.registrar_3.id(), ctx.naming.id(), ctx.fungible_asset.faucet_id(), 1, payout_serial).await?;
+    let mut chain = setup_referral_revenue(&mut ctx, std::slice::from_ref(&claim_note)).await?;
+
+    let result = execute_note(&mut chain, claim_note.id(), &mut ctx.naming).await;
+
+    assert!(result.is_err(), "Expected claim without referral revenue to fail, but it succeeded");
+    Ok(())
+}
+
+
+#[tokio::test]
+async fn test_referrer_revenue_per_token() -> anyhow::Result<()> {
+    let mut ctx = init_naming().await?;
+    let second_token: AccountId = ACCOUNT_ID_PUBLIC_FUNGIBLE_FAUCET_2.try_into()?;
+
+    let set_second_prices_note = create_set_prices_note(ctx.owner.id(), ctx.naming.id(), &get_test_price_entries(second_token)).await?;
+    add_note_to_builder(&mut ctx.builder, set_second_prices_note.clone())?;
+
+    let domain = encode_domain_as_felts("test2");
+    let register_note_inputs = NoteInputs::new([
+        Felt::new(ctx.registrar_2.id().suffix().as_int()),
+        Felt::new(ctx.registrar_2.id().prefix().as_u64()),
+        Felt::new(0),
+        Felt::new(0),
+        Felt::new(second_token.suffix().as_int()),
+        second_token.prefix().as_felt(),
+        Felt::new(0),
+        Felt::new(0),
+        domain[0],
+        domain[1],
+        domain[2],
+        domain[3],
+        Felt::new(1), // register length
+        Felt::new(0),
+        Felt::new(0),
+        Felt::new(0),
+    ].to_vec())?;
+
+    let cost = FungibleAsset::new(second_token, 123)?;
+    let register_note = create_note_for_naming("register_with_referrer".to_string(), register_note_inputs, ctx.registrar_1.id(), ctx.naming.id(), NoteAssets::new(vec![cost.into()])?).await?;
+
+    let payout_serial = Word::new([Felt::new(9), Felt::new(9), Felt::new(9), Felt::new(9)]);
+    let claim_note = create_claim_referral_earnings_note(ctx.registrar_2.id(), ctx.naming.id(), second_token, 25, payout_serial).await?;
+
+    let mut chain = setup_referral_revenue(&mut ctx, &[set_second_prices_note.clone(), register_note.clone(), claim_note.clone()]).await?;
+    execute_note(&mut chain, set_second_prices_note.id(), &mut ctx.naming).await?;
+    execute_note(&mut chain, register_note.id(), &mut ctx.naming).await?;
+
+    // 20% of 555 in the first token, 20% of 123 in the second one
+    let first_token_slot = ctx.naming.storage().get_map_item(7, referrer_revenue_key(ctx.registrar_2.id(), ctx.fungible_asset.faucet_id()))?;
+    let second_token_slot = ctx.naming.storage().get_map_item(7, referrer_revenue_key(ctx.registrar_2.id(), second_token))?;
+    assert_eq!(first_token_slot.first().unwrap().as_int(), 111);
+    assert_eq!(second_token_slot.first().unwrap().as_int(), 24);
+
+    // Claiming more than the second token balance must fail even though the first token has more
+    let result = execute_note(&mut chain, claim_note.id(), &mut ctx.naming).await;
+    assert!(result.is_err(), "Expected claim above the per-token balance to fail, but it succeeded");
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_withdraw_assets_keeps_referral_revenue() -> anyhow::Result<()> {
+    let mut ctx = init_naming().await?;
+
+    let stray = FungibleAsset::new(ctx.fungible_asset.faucet_id(), 100)?;
+    let stray_note = create_p2id_note_exact(ctx.registrar_1.id(), ctx.naming.id(), vec![stray.into()], NoteType::Public, Felt::new(0), Word::default())?;
+    let payout_serial = Word::new([Felt::new(7), Felt::new(7), Felt::new(7), Felt::new(7)]);
+    // Vault holds 555 + 100, of which 444 is protocol revenue and 111 is owed to the referrer
+    let withdraw_note = create_withdraw_assets_note(ctx.owner.id(), ctx.naming.id(), ctx.fungible_asset.faucet_id(), 101, payout_serial).await?;
+
+    let mut chain = setup_referral_revenue(&mut ctx, &[stray_note.clone(), withdraw_note.clone()]).await?;
+    execute_note(&mut chain, stray_note.id(), &mut ctx.naming).await?;
+
+    let result = execute_note(&mut chain, withdraw_note.id(), &mut ctx.naming).await;
+    assert!(result.is_err(), "Expected withdrawing referral revenue to fail, but it succeeded");
+
+    let outstanding_slot = ctx.naming.storage().get_map_item(14, Word::new([Felt::new(ctx.fungible_asset.faucet_id().suffix().as_int()), Felt::new(ctx.fungible_asset.faucet_id().prefix().as_u64()), Felt::new(0), Felt::new(0)]))?;
+    assert_eq!(outstanding_slot.first().unwrap().as_int(), 111);
+    Ok(())
+}
+
+
+#[tokio::test]
+async fn test_register_under_referrer_higher_amount() -> anyhow::Result<()> {
+    let mut ctx = init_naming().await?;
+
+    let refund_serial = Word::new([Felt::new(11), Felt::new(12), Felt::new(13), Felt::new(14)]);
+    let cost = FungibleAsset::new(ctx.fungible_asset.faucet_id(), 600)?;
+    let register_note = create_register_with_referrer_note(ctx.registrar_3.id(), ctx.naming.id(), ctx.registrar_2.id(), ctx.fungible_asset.faucet_id(), encode_domain("test2"), 1, None, NoteAssets::new(vec![cost.into()])?, refund_serial).await?;
+
+    let mut chain = setup_referral_revenue(&mut ctx, std::slice::from_ref(&register_note)).await?;
+
+    // "test2" costs 123, the rest goes back to registrar_3
+    let change = FungibleAsset::new(ctx.fungible_asset.faucet_id(), 477)?;
+    let refund_note = create_p2id_note_exact(ctx.naming.id(), ctx.registrar_3.id(), vec![change.into()], NoteType::Public, Felt::new(0), refund_serial)?;
+    let executed_tx = execute_note_with_expected_outputs(&mut chain, register_note.id(), &mut ctx.naming, vec![refund_note.clone()]).await?;
+
+    assert_eq!(executed_tx.output_notes().get_note(0).id(), refund_note.id());
+
+    let referrer_slot = ctx.naming.storage().get_map_item(7, referrer_revenue_key(ctx.registrar_2.id(), ctx.fungible_asset.faucet_id()))?;
+    assert_eq!(referrer_slot.get(0).unwrap().as_int(), 135);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_register_under_referrer_for_other_owner() -> anyhow::Result<()> {
+    let mut ctx = init_naming().await?;
+
+    let refund_serial = Word::new([Felt::new(11), Felt::new(12), Felt::new(13), Felt::new(14)]);
+    let cost = FungibleAsset::new(ctx.fungible_asset.faucet_id(), 123)?;
+    let register_note = create_register_with_referrer_note(ctx.registrar_3.id(), ctx.naming.id(), ctx.registrar_2.id(), ctx.fungible_asset.faucet_id(), encode_domain("test2"), 1, Some(ctx.registrar_1.id()), NoteAssets::new(vec![cost.into()])?, refund_serial).await?;
+
+    let mut chain = setup_referral_revenue(&mut ctx, std::slice::from_ref(&register_note)).await?;
+    let referrer_revenue = ctx.naming.storage().get_map_item(7, referrer_revenue_key(ctx.registrar_2.id(), ctx.fungible_asset.faucet_id()))?.first().unwrap().as_int();
+    execute_note(&mut chain, register_note.id(), &mut ctx.naming).await?;
+
+    // registrar_3 paid, registrar_1 owns the domain and the referrer still earns its fee
+    let domain_owner_slot = ctx.naming.storage().get_map_item(5, encode_domain("test2"))?;
+    assert_eq!(domain_owner_slot.get(0).unwrap().as_int(), ctx.registrar_1.id().suffix().as_int());
+    assert_eq!(domain_owner_slot.get(1).unwrap().as_int(), ctx.registrar_1.id().prefix().as_u64());
+
+    let referrer_slot = ctx.naming.storage().get_map_item(7, referrer_revenue_key(ctx.registrar_2.id(), ctx.fungible_asset.faucet_id()))?;
+    assert!(referrer_slot.first().unwrap().as_int() > referrer_revenue);
+    Ok(())
+}
@@ -1,11 +1,12 @@
 mod test_utils;
 
-use miden_client::{asset::FungibleAsset, note::{NoteAssets, NoteInputs}};
+use miden_client::{account::AccountId, asset::FungibleAsset, note::{NoteAssets, NoteInputs, NoteType}, testing::account_id::ACCOUNT_ID_PUBLIC_FUNGIBLE_FAUCET_2};
 use miden_crypto::{Felt, Word};
-use midenname_contracts::domain::{encode_domain, encode_domain_as_felts, unsafe_encode_domain};
+use miden_lib::note::WellKnownNote;
+use midenname_contracts::{config::RegistryParameters, domain::{encode_domain, encode_domain_as_felts, encode_domain_v2, unsafe_encode_domain, unsafe_encode_domain_codes, unsafe_encode_domain_codes_v2, MAX_DOMAIN_LENGTH}, notes::{build_refund_note_inputs, build_registration_inputs, create_extend_domain_note, create_register_note, create_set_discount_tier_note, create_set_prices_note, create_set_primary_name_note}, quote::quote_max_extension, storage::discount_tier_key};
 use test_utils::init_naming;
 
-use crate::test_utils::{add_note_to_builder, create_note_for_naming, execute_note, execute_notes_and_build_chain, get_test_prices, create_note_for_naming_with_custom_serial_num};
+use crate::test_utils::{add_note_to_builder, create_note_for_naming, create_p2id_note_exact, execute_note, execute_note_with_expected_outputs, execute_notes_and_build_chain, get_test_prices, create_note_for_naming_with_custom_serial_num};
 
 #[tokio::test]
 async fn test_naming_initialize() -> anyhow::Result<()> {
@@ -18,12 +19,13 @@ async fn test_naming_initialize() -> anyhow::Result<()> {
     
     let init_slot = ctx.naming.storage().get_item(0)?;
     let owner_slot = ctx.naming.storage().get_item(1)?;
-    let one_year_slot = ctx.naming.storage().get_item(13)?;
+    let parameters_slot = ctx.naming.storage().get_item(13)?;
 
     assert_eq!(init_slot.get(0).unwrap().as_int(), 1);
     assert_eq!(owner_slot.get(1).unwrap().as_int(), ctx.owner.id().prefix().as_u64());
     assert_eq!(owner_slot.get(0).unwrap().as_int(), ctx.owner.id().suffix().as_int());
-    assert_eq!(one_year_slot.get(0).unwrap().as_int(), 500);
+    assert_eq!(parameters_slot, RegistryParameters::with_one_year(500).to_word());
+    assert_eq!(ctx.naming.storage().get_item(15)?, WellKnownNote::P2ID.script_root());
 
     // Assert prices
     let mock_prices = get_test_prices();
@@ -109,7 +111,7 @@ async fn test_naming_register_activate() -> anyhow::Result<()> {
 
     assert_eq!(domain_to_id.get(0).unwrap().as_int(), ctx.registrar_1.id().suffix().as_int()); // Now domain mapping must be matched
     assert_eq!(domain_to_id.get(1).unwrap().as_int(), ctx.registrar_1.id().prefix().as_u64());
-    assert_eq!(id_to_domain, domain_word);
+    assert_eq!(id_to_domain, Word::default()); // Primary name is set separately
     Ok(())
 }
 
@@ -318,6 +320,8 @@ async fn test_naming_register_two_domains_activate_after() -> anyhow::Result<()>
 
     let activate_note_2 = create_note_for_naming("activate_domain".to_string(), NoteInputs::new(second_domain_word.to_vec())?, ctx.registrar_1.id(), ctx.naming.id(), NoteAssets::new(vec![])?).await?;
     add_note_to_builder(&mut ctx.builder, activate_note_2.clone())?;  
+    let primary_note = create_set_primary_name_note(ctx.registrar_1.id(), ctx.naming.id(), domain_word).await?;
+    add_note_to_builder(&mut ctx.builder, primary_note.clone())?;
 
     // Execution
     let mut chain = execute_notes_and_build_chain(ctx.builder, &[ctx.initialize_note.id(), ctx.set_prices_note.id()], &mut ctx.naming).await?;
@@ -356,6 +360,13 @@ async fn test_naming_register_two_domains_activate_after() -> anyhow::Result<()>
 
     assert_eq!(domain_to_id.get(0).unwrap().as_int(), ctx.registrar_1.id().suffix().as_int()); // Now domain mapping must be matched
     assert_eq!(domain_to_id.get(1).unwrap().as_int(), ctx.registrar_1.id().prefix().as_u64());
+    assert_eq!(id_to_domain, Word::default()); // Activation only maps forward
+
+    // Choose first domain as primary name
+
+    execute_note(&mut chain, primary_note.id(), &mut ctx.naming).await?;
+
+    let id_to_domain = ctx.naming.storage().get_map_item(3, Word::new([Felt::new(ctx.registrar_1.id().suffix().as_int()), Felt::new(ctx.registrar_1.id().prefix().as_u64()), Felt::new(0), Felt::new(0)]))?;
     assert_eq!(id_to_domain, domain_word);
 
     // Register new domain
@@ -381,7 +392,7 @@ async fn test_naming_register_two_domains_activate_after() -> anyhow::Result<()>
 
     assert_eq!(domain_to_id.get(0).unwrap().as_int(), ctx.registrar_1.id().suffix().as_int()); // Now domain mapping must be matched
     assert_eq!(domain_to_id.get(1).unwrap().as_int(), ctx.registrar_1.id().prefix().as_u64());
-    assert_eq!(id_to_domain, second_domain_word);
+    assert_eq!(id_to_domain, domain_word); // Primary name is kept
 
     // Check first domain mapping
 
@@ -434,31 +445,25 @@ async fn test_naming_register_less_amount() -> anyhow::Result<()> {
 async fn test_naming_register_higher_amount() -> anyhow::Result<()> {
     let mut ctx = init_naming().await?;
 
-    let domain = encode_domain_as_felts("test".to_string());
-    let register_note_inputs = NoteInputs::new([
-        Felt::new(ctx.fungible_asset.faucet_id().suffix().as_int()),
-        ctx.fungible_asset.faucet_id().prefix().as_felt(),
-        Felt::new(0),
-        Felt::new(0),
-        domain[0],
-        domain[1],
-        domain[2],
-        domain[3],
-        Felt::new(1), // register length
-        Felt::new(0),
-        Felt::new(0),
-        Felt::new(0),
-    ].to_vec())?;
+    let domain_word = encode_domain("test".to_string());
+    let refund_serial = Word::new([Felt::new(11), Felt::new(12), Felt::new(13), Felt::new(14)]);
     
     let cost = FungibleAsset::new(ctx.fungible_asset.faucet_id(), 1200)?;
     let register_asset = NoteAssets::new(vec![cost.into()])?;
-    let note = create_note_for_naming("register_name".to_string(), register_note_inputs, ctx.registrar_1.id(), ctx.naming.id(), register_asset).await?;
+    let note = create_register_note(ctx.registrar_1.id(), ctx.naming.id(), ctx.fungible_asset.faucet_id(), domain_word, 1, None, register_asset, refund_serial).await?;
     add_note_to_builder(&mut ctx.builder, note.clone())?;
     let mut chain = execute_notes_and_build_chain(ctx.builder, &[ctx.initialize_note.id(), ctx.set_prices_note.id()], &mut ctx.naming).await?;
-    execute_note(&mut chain, note.id(), &mut ctx.naming).await?;
+
+    let change = FungibleAsset::new(ctx.fungible_asset.faucet_id(), 645)?;
+    let refund_note = create_p2id_note_exact(ctx.naming.id(), ctx.registrar_1.id(), vec![change.into()], NoteType::Public, Felt::new(0), refund_serial)?;
+    let executed_tx = execute_note_with_expected_outputs(&mut chain, note.id(), &mut ctx.naming, vec![refund_note.clone()]).await?;
+
+    assert_eq!(executed_tx.output_notes().num_notes(), 1);
+    assert_eq!(executed_tx.output_notes().get_note(0).id(), refund_note.id()); // Change goes back to the registrar
 
     let total_revenue_slot = ctx.naming.storage().get_map_item(10, Word::new([Felt::new(ctx.fungible_asset.faucet_id().suffix().as_int()), Felt::new(ctx.fungible_asset.faucet_id().prefix().as_u64()), Felt::new(0), Felt::new(0)]))?;
     assert_eq!(total_revenue_slot.get(0).unwrap().as_int(), 555); // Protocol only saves actual cost as revenue
+    assert_eq!(ctx.naming.vault().get_balance(ctx.fungible_asset.faucet_id())?, 555);
 
     let total_domain_count = ctx.naming.storage().get_item(9)?;
     assert_eq!(total_domain_count.get(0).unwrap().as_int(), 1);
@@ -466,6 +471,102 @@ async fn test_naming_register_higher_amount() -> anyhow::Result<()> {
     Ok(())
 }
 
+#[tokio::test]
+async fn test_naming_register_refund_details_misplaced() -> anyhow::Result<()> {
+    let mut ctx = init_naming().await?;
+
+    let refund_serial = Word::new([Felt::new(11), Felt::new(12), Felt::new(13), Felt::new(14)]);
+    // Refund details without the OWNER word in front of them
+    let mut inputs = build_registration_inputs(ctx.fungible_asset.faucet_id(), encode_domain("test"), 1);
+    inputs.extend(build_refund_note_inputs(ctx.registrar_1.id(), refund_serial));
+
+    let cost = FungibleAsset::new(ctx.fungible_asset.faucet_id(), 1200)?;
+    let note = create_note_for_naming("register_name".to_string(), NoteInputs::new(inputs)?, ctx.registrar_1.id(), ctx.naming.id(), NoteAssets::new(vec![cost.into()])?).await?;
+    add_note_to_builder(&mut ctx.builder, note.clone())?;
+    let mut chain = execute_notes_and_build_chain(ctx.builder, &[ctx.initialize_note.id(), ctx.set_prices_note.id()], &mut ctx.naming).await?;
+
+    let result = execute_note(&mut chain, note.id(), &mut ctx.naming).await;
+    assert!(result.is_err(), "Expected refund with misplaced refund details to fail, but it succeeded");
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_naming_register_with_foreign_asset() -> anyhow::Result<()> {
+    let mut ctx = init_naming().await?;
+
+    let domain_word = encode_domain("test".to_string());
+    let refund_serial = Word::new([Felt::new(11), Felt::new(12), Felt::new(13), Felt::new(14)]);
+    let foreign_token: AccountId = ACCOUNT_ID_PUBLIC_FUNGIBLE_FAUCET_2.try_into()?;
+
+    let cost = FungibleAsset::new(ctx.fungible_asset.faucet_id(), 555)?;
+    let foreign_asset = FungibleAsset::new(foreign_token, 100)?;
+    let register_asset = NoteAssets::new(vec![cost.into(), foreign_asset.into()])?;
+    let note = create_register_note(ctx.registrar_1.id(), ctx.naming.id(), ctx.fungible_asset.faucet_id(), domain_word, 1, None, register_asset, refund_serial).await?;
+    add_note_to_builder(&mut ctx.builder, note.clone())?;
+    let mut chain = execute_notes_and_build_chain(ctx.builder, &[ctx.initialize_note.id(), ctx.set_prices_note.id()], &mut ctx.naming).await?;
+
+    let refund_note = create_p2id_note_exact(ctx.naming.id(), ctx.registrar_1.id(), vec![foreign_asset.into()], NoteType::Public, Felt::new(0), refund_serial)?;
+    let executed_tx = execute_note_with_expected_outputs(&mut chain, note.id(), &mut ctx.naming, vec![refund_note.clone()]).await?;
+
+    assert_eq!(executed_tx.output_notes().num_notes(), 1);
+    assert_eq!(executed_tx.output_notes().get_note(0).id(), refund_note.id());
+    assert_eq!(ctx.naming.vault().get_balance(foreign_token)?, 0);
+    assert_eq!(ctx.naming.vault().get_balance(ctx.fungible_asset.faucet_id())?, 555);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_naming_register_exact_amount_no_refund() -> anyhow::Result<()> {
+    let mut ctx = init_naming().await?;
+
+    let domain_word = encode_domain("test".to_string());
+    let refund_serial = Word::new([Felt::new(11), Felt::new(12), Felt::new(13), Felt::new(14)]);
+
+    let cost = FungibleAsset::new(ctx.fungible_asset.faucet_id(), 555)?;
+    let note = create_register_note(ctx.registrar_1.id(), ctx.naming.id(), ctx.fungible_asset.faucet_id(), domain_word, 1, None, NoteAssets::new(vec![cost.into()])?, refund_serial).await?;
+    add_note_to_builder(&mut ctx.builder, note.clone())?;
+    let mut chain = execute_notes_and_build_chain(ctx.builder, &[ctx.initialize_note.id(), ctx.set_prices_note.id()], &mut ctx.naming).await?;
+
+    let executed_tx = execute_note_with_expected_outputs(&mut chain, note.id(), &mut ctx.naming, vec![]).await?;
+    assert_eq!(executed_tx.output_notes().num_notes(), 0);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_naming_register_for_other_owner() -> anyhow::Result<()> {
+    let mut ctx = init_naming().await?;
+
+    let domain_word = encode_domain("test".to_string());
+    let refund_serial = Word::new([Felt::new(11), Felt::new(12), Felt::new(13), Felt::new(14)]);
+
+    // registrar_1 pays, registrar_2 owns the domain
+    let cost = FungibleAsset::new(ctx.fungible_asset.faucet_id(), 555)?;
+    let note = create_register_note(ctx.registrar_1.id(), ctx.naming.id(), ctx.fungible_asset.faucet_id(), domain_word, 1, Some(ctx.registrar_2.id()), NoteAssets::new(vec![cost.into()])?, refund_serial).await?;
+    let activate_by_payer = create_note_for_naming("activate_domain".to_string(), NoteInputs::new(domain_word.to_vec())?, ctx.registrar_1.id(), ctx.naming.id(), NoteAssets::new(vec![])?).await?;
+    // Note ids do not cover the sender, so both activate notes need their own serial
+    let activate_serial = Word::new([Felt::new(21), Felt::new(22), Felt::new(23), Felt::new(24)]);
+    let activate_by_owner = create_note_for_naming_with_custom_serial_num("activate_domain".to_string(), NoteInputs::new(domain_word.to_vec())?, ctx.registrar_2.id(), ctx.naming.id(), NoteAssets::new(vec![])?, activate_serial).await?;
+    add_note_to_builder(&mut ctx.builder, note.clone())?;
+    add_note_to_builder(&mut ctx.builder, activate_by_payer.clone())?;
+    add_note_to_builder(&mut ctx.builder, activate_by_owner.clone())?;
+    let mut chain = execute_notes_and_build_chain(ctx.builder, &[ctx.initialize_note.id(), ctx.set_prices_note.id(), note.id()], &mut ctx.naming).await?;
+
+    let domain_owner_slot = ctx.naming.storage().get_map_item(5, domain_word)?;
+    assert_eq!(domain_owner_slot.get(0).unwrap().as_int(), ctx.registrar_2.id().suffix().as_int());
+    assert_eq!(domain_owner_slot.get(1).unwrap().as_int(), ctx.registrar_2.id().prefix().as_u64());
+
+    let result = execute_note(&mut chain, activate_by_payer.id(), &mut ctx.naming).await;
+    assert!(result.is_err(), "Expected activation by the payer to fail, but it succeeded");
+
+    execute_note(&mut chain, activate_by_owner.id(), &mut ctx.naming).await?;
+    let domain_to_id = ctx.naming.storage().get_map_item(4, domain_word)?;
+    assert_eq!(domain_to_id.first().unwrap().as_int(), ctx.registrar_2.id().suffix().as_int());
+    assert_eq!(domain_to_id.get(1).unwrap().as_int(), ctx.registrar_2.id().prefix().as_u64());
+    Ok(())
+}
+
 #[tokio::test]
 async fn test_naming_register_wrong_letter_length() -> anyhow::Result<()> {
     let mut ctx = init_naming().await?;
@@ -686,9 +787,8 @@ async fn test_expired_domain_rebuy() -> anyhow::Result<()> {
     let activate_note_1 = create_note_for_naming_with_custom_serial_num("activate_domain".to_string(), NoteInputs::new(domain_word.to_vec())?, ctx.registrar_1.id(), ctx.naming.id(), NoteAssets::new(vec![])?, Word::new([Felt::new(1), Felt::new(2), Felt::new(3), Felt::new(4)])).await?;
     add_note_to_builder(&mut ctx.builder, activate_note_1.clone())?; 
 
-    let cost = FungibleAsset::new(ctx.fungible_asset.faucet_id(), 556)?; // Pay more to not create same nullifier
-    let register_asset = NoteAssets::new(vec![cost.into()])?;
-    let register_note_2 = create_note_for_naming("register_name".to_string(), register_note_inputs.clone(), ctx.registrar_2.id(), ctx.naming.id(), register_asset.clone()).await?;
+    // Different serial number to not create same nullifier
+    let register_note_2 = create_note_for_naming_with_custom_serial_num("register_name".to_string(), register_note_inputs.clone(), ctx.registrar_2.id(), ctx.naming.id(), register_asset.clone(), Word::new([Felt::new(1), Felt::new(2), Felt::new(3), Felt::new(6)])).await?;
     add_note_to_builder(&mut ctx.builder, register_note_2.clone())?;
 
     let activate_note_2 = create_note_for_naming_with_custom_serial_num("activate_domain".to_string(), NoteInputs::new(domain_word.to_vec())?, ctx.registrar_2.id(), ctx.naming.id(), NoteAssets::new(vec![])?, Word::new([Felt::new(1), Felt::new(2), Felt::new(3), Felt::new(5)])).await?;
@@ -734,7 +834,7 @@ async fn test_expired_domain_rebuy() -> anyhow::Result<()> {
     
     assert_eq!(domain_to_id.get(0).unwrap().as_int(), ctx.registrar_2.id().suffix().as_int()); // Now domain mapping must be matched
     assert_eq!(domain_to_id.get(1).unwrap().as_int(), ctx.registrar_2.id().prefix().as_u64());
-    assert_eq!(id_to_domain, domain_word);
+    assert_eq!(id_to_domain, Word::default()); // Primary name is set separately
     Ok(())
 }
 
@@ -824,40 +924,83 @@ async fn test_extend_expired_domain() -> anyhow::Result<()> {
 }
 
 #[tokio::test]
-async fn test_extend_domain_by_not_owner() -> anyhow::Result<()> {
-        let mut ctx = init_naming().await?;
+async fn test_extend_domain_by_third_party() -> anyhow::Result<()> {
+    let mut ctx = init_naming().await?;
 
-    let domain = encode_domain_as_felts("test".to_string());
-    let register_note_inputs = NoteInputs::new([
-        Felt::new(ctx.fungible_asset.faucet_id().suffix().as_int()),
-        ctx.fungible_asset.faucet_id().prefix().as_felt(),
-        Felt::new(0),
-        Felt::new(0),
-        domain[0],
-        domain[1],
-        domain[2],
-        domain[3],
-        Felt::new(1), // register length
-        Felt::new(0),
-        Felt::new(0),
-        Felt::new(0),
-    ].to_vec())?;
+    let domain_word = encode_domain("test".to_string());
+    let refund_serial = Word::new([Felt::new(11), Felt::new(12), Felt::new(13), Felt::new(14)]);
+    let token = ctx.fungible_asset.faucet_id();
 
-    let cost = FungibleAsset::new(ctx.fungible_asset.faucet_id(), 555)?;
-    let register_asset = NoteAssets::new(vec![cost.into()])?;
-    let register_note = create_note_for_naming("register_name".to_string(), register_note_inputs.clone(), ctx.registrar_1.id(), ctx.naming.id(), register_asset.clone()).await?;
+    let cost = FungibleAsset::new(token, 555)?;
+    let register_note = create_register_note(ctx.registrar_1.id(), ctx.naming.id(), token, domain_word, 1, None, NoteAssets::new(vec![cost.into()])?, refund_serial).await?;
     add_note_to_builder(&mut ctx.builder, register_note.clone())?;
 
-    let extend_note = create_note_for_naming("extend_domain".to_string(), register_note_inputs.clone(), ctx.registrar_2.id(), ctx.naming.id(), register_asset.clone()).await?;
+    // registrar_2 renews registrar_1's domain for 3 years with the multi-year discount
+    let cost = FungibleAsset::new(token, 1167)?;
+    let extend_note = create_extend_domain_note(ctx.registrar_2.id(), ctx.naming.id(), token, domain_word, 3, NoteAssets::new(vec![cost.into()])?, refund_serial).await?;
     add_note_to_builder(&mut ctx.builder, extend_note.clone())?;
 
+    let cost = FungibleAsset::new(token, 555 * 11)?;
+    let too_long_note = create_extend_domain_note(ctx.registrar_2.id(), ctx.naming.id(), token, domain_word, 11, NoteAssets::new(vec![cost.into()])?, refund_serial).await?;
+    add_note_to_builder(&mut ctx.builder, too_long_note.clone())?;
+
     let mut chain = execute_notes_and_build_chain(ctx.builder, &[ctx.initialize_note.id(), ctx.set_prices_note.id()], &mut ctx.naming).await?;
 
     execute_note(&mut chain, register_note.id(), &mut ctx.naming).await?;
+    let current_expiry = ctx.naming.storage().get_map_item(12, domain_word)?.first().unwrap().as_int();
 
-    let result = execute_note(&mut chain, extend_note.id(), &mut ctx.naming).await;
+    execute_note_with_expected_outputs(&mut chain, extend_note.id(), &mut ctx.naming, vec![]).await?;
 
-    assert!(result.is_err(), "Expected fail");
+    let updated_expiry = ctx.naming.storage().get_map_item(12, domain_word)?.first().unwrap().as_int();
+    assert_eq!(updated_expiry, current_expiry + 3 * ctx.one_year as u64);
+
+    // Ownership does not move to the payer
+    let domain_owner_slot = ctx.naming.storage().get_map_item(5, domain_word)?;
+    assert_eq!(domain_owner_slot.get(0).unwrap().as_int(), ctx.registrar_1.id().suffix().as_int());
+    assert_eq!(domain_owner_slot.get(1).unwrap().as_int(), ctx.registrar_1.id().prefix().as_u64());
+
+    let result = execute_note(&mut chain, too_long_note.id(), &mut ctx.naming).await;
+    assert!(result.is_err(), "Expected extension by more than 10 years to fail, but it succeeded");
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_extend_domain_capped_at_max_years() -> anyhow::Result<()> {
+    let mut ctx = init_naming().await?;
+
+    let domain_word = encode_domain("test".to_string());
+    let refund_serial = Word::new([Felt::new(11), Felt::new(12), Felt::new(13), Felt::new(14)]);
+    let token = ctx.fungible_asset.faucet_id();
+
+    let cost = FungibleAsset::new(token, 555)?;
+    let register_note = create_register_note(ctx.registrar_1.id(), ctx.naming.id(), token, domain_word, 1, None, NoteAssets::new(vec![cost.into()])?, refund_serial).await?;
+    // 50% discount from 5 years, 278 per year
+    let cost = FungibleAsset::new(token, 2780)?;
+    let extend_10_note = create_extend_domain_note(ctx.registrar_1.id(), ctx.naming.id(), token, domain_word, 10, NoteAssets::new(vec![cost.into()])?, refund_serial).await?;
+    let cost = FungibleAsset::new(token, 2502)?;
+    let extend_9_note = create_extend_domain_note(ctx.registrar_1.id(), ctx.naming.id(), token, domain_word, 9, NoteAssets::new(vec![cost.into()])?, refund_serial).await?;
+    let cost = FungibleAsset::new(token, 555)?;
+    let extend_1_note = create_extend_domain_note(ctx.registrar_1.id(), ctx.naming.id(), token, domain_word, 1, NoteAssets::new(vec![cost.into()])?, refund_serial).await?;
+    for note in [&register_note, &extend_10_note, &extend_9_note, &extend_1_note] {
+        add_note_to_builder(&mut ctx.builder, note.clone())?;
+    }
+
+    let mut chain = execute_notes_and_build_chain(ctx.builder, &[ctx.initialize_note.id(), ctx.set_prices_note.id(), register_note.id()], &mut ctx.naming).await?;
+
+    // Registered for one year, expiry can reach 10 years from now
+    let timestamp = chain.latest_block_header().timestamp();
+    assert_eq!(quote_max_extension(ctx.naming.storage(), domain_word, timestamp)?, 9);
+
+    let result = execute_note(&mut chain, extend_10_note.id(), &mut ctx.naming).await;
+    assert!(result.is_err(), "Expected extension past 10 years from now to fail, but it succeeded");
+
+    execute_note(&mut chain, extend_9_note.id(), &mut ctx.naming).await?;
+
+    let timestamp = chain.latest_block_header().timestamp();
+    assert_eq!(quote_max_extension(ctx.naming.storage(), domain_word, timestamp)?, 0);
+
+    let result = execute_note(&mut chain, extend_1_note.id(), &mut ctx.naming).await;
+    assert!(result.is_err(), "Expected extension past 10 years from now to fail, but it succeeded");
     Ok(())
 }
 
@@ -990,4 +1133,234 @@ let mut ctx = init_naming().await?;
     assert_eq!(total_revenue_slot.get(0).unwrap().as_int(), discounted_cost);
 
     Ok(())
-}
\ No newline at end of file
+}
+
+#[tokio::test]
+async fn test_register_with_custom_discount_tier() -> anyhow::Result<()> {
+    let mut ctx = init_naming().await?;
+    let token = ctx.fungible_asset.faucet_id();
+    let refund_serial = Word::new([Felt::new(11), Felt::new(12), Felt::new(13), Felt::new(14)]);
+
+    // 20% off from 2 years, 444 per year
+    let tier_note = create_set_discount_tier_note(ctx.owner.id(), ctx.naming.id(), 2, 2000).await?;
+    let discounted_cost = 888;
+    let cost = FungibleAsset::new(token, discounted_cost)?;
+    let register_note = create_register_note(ctx.registrar_1.id(), ctx.naming.id(), token, encode_domain("test"), 2, None, NoteAssets::new(vec![cost.into()])?, refund_serial).await?;
+    add_note_to_builder(&mut ctx.builder, tier_note.clone())?;
+    add_note_to_builder(&mut ctx.builder, register_note.clone())?;
+
+    let mut chain = execute_notes_and_build_chain(ctx.builder, &[ctx.initialize_note.id(), ctx.set_prices_note.id(), tier_note.id()], &mut ctx.naming).await?;
+    assert_eq!(ctx.naming.storage().get_map_item(26, discount_tier_key(2))?.first().unwrap().as_int(), 2000);
+
+    execute_note(&mut chain, register_note.id(), &mut ctx.naming).await?;
+    let total_revenue_slot = ctx.naming.storage().get_map_item(10, Word::new([token.suffix(), token.prefix().as_felt(), Felt::new(0), Felt::new(0)]))?;
+    assert_eq!(total_revenue_slot.get(0).unwrap().as_int(), discounted_cost);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_set_discount_tier_by_not_owner() -> anyhow::Result<()> {
+    let mut ctx = init_naming().await?;
+
+    let tier_note = create_set_discount_tier_note(ctx.registrar_1.id(), ctx.naming.id(), 1, 5000).await?;
+    add_note_to_builder(&mut ctx.builder, tier_note.clone())?;
+
+    let mut chain = execute_notes_and_build_chain(ctx.builder, &[ctx.initialize_note.id(), ctx.set_prices_note.id()], &mut ctx.naming).await?;
+    let result = execute_note(&mut chain, tier_note.id(), &mut ctx.naming).await;
+
+    assert!(result.is_err(), "Expected set_discount_tier from non-owner to fail, but it succeeded");
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_set_discount_tier_full_discount_rejected() -> anyhow::Result<()> {
+    let mut ctx = init_naming().await?;
+
+    let tier_note = create_set_discount_tier_note(ctx.owner.id(), ctx.naming.id(), 1, 10000).await?;
+    add_note_to_builder(&mut ctx.builder, tier_note.clone())?;
+
+    let mut chain = execute_notes_and_build_chain(ctx.builder, &[ctx.initialize_note.id(), ctx.set_prices_note.id()], &mut ctx.naming).await?;
+    let result = execute_note(&mut chain, tier_note.id(), &mut ctx.naming).await;
+
+    assert!(result.is_err(), "Expected a 100% discount tier to fail, but it succeeded");
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_extend_domain_higher_amount() -> anyhow::Result<()> {
+    let mut ctx = init_naming().await?;
+
+    let domain_word = encode_domain("test".to_string());
+    let refund_serial = Word::new([Felt::new(11), Felt::new(12), Felt::new(13), Felt::new(14)]);
+
+    let cost = FungibleAsset::new(ctx.fungible_asset.faucet_id(), 555)?;
+    let register_note = create_register_note(ctx.registrar_1.id(), ctx.naming.id(), ctx.fungible_asset.faucet_id(), domain_word, 1, None, NoteAssets::new(vec![cost.into()])?, refund_serial).await?;
+    add_note_to_builder(&mut ctx.builder, register_note.clone())?;
+
+    let extend_cost = FungibleAsset::new(ctx.fungible_asset.faucet_id(), 600)?;
+    let extend_note = create_extend_domain_note(ctx.registrar_1.id(), ctx.naming.id(), ctx.fungible_asset.faucet_id(), domain_word, 1, NoteAssets::new(vec![extend_cost.into()])?, refund_serial).await?;
+    add_note_to_builder(&mut ctx.builder, extend_note.clone())?;
+
+    let mut chain = execute_notes_and_build_chain(ctx.builder, &[ctx.initialize_note.id(), ctx.set_prices_note.id(), register_note.id()], &mut ctx.naming).await?;
+
+    let change = FungibleAsset::new(ctx.fungible_asset.faucet_id(), 45)?;
+    let refund_note = create_p2id_note_exact(ctx.naming.id(), ctx.registrar_1.id(), vec![change.into()], NoteType::Public, Felt::new(0), refund_serial)?;
+    let executed_tx = execute_note_with_expected_outputs(&mut chain, extend_note.id(), &mut ctx.naming, vec![refund_note.clone()]).await?;
+
+    assert_eq!(executed_tx.output_notes().get_note(0).id(), refund_note.id());
+    assert_eq!(ctx.naming.vault().get_balance(ctx.fungible_asset.faucet_id())?, 1110);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_register_price_for_every_length() -> anyhow::Result<()> {
+    let mut ctx = init_naming().await?;
+    let mock_prices = get_test_prices();
+    let refund_serial = Word::new([Felt::new(11), Felt::new(12), Felt::new(13), Felt::new(14)]);
+
+    let mut notes = Vec::new();
+    for length in 1..=MAX_DOMAIN_LENGTH {
+        // Lengths from the breakpoint (5) onwards use the 5 letter price
+        let price = mock_prices[length.min(5)].as_int();
+        let cost = FungibleAsset::new(ctx.fungible_asset.faucet_id(), price)?;
+        let note = create_register_note(ctx.registrar_1.id(), ctx.naming.id(), ctx.fungible_asset.faucet_id(), encode_domain("a".repeat(length)), 1, None, NoteAssets::new(vec![cost.into()])?, refund_serial).await?;
+        add_note_to_builder(&mut ctx.builder, note.clone())?;
+        notes.push((note, price));
+    }
+
+    let mut chain = execute_notes_and_build_chain(ctx.builder, &[ctx.initialize_note.id(), ctx.set_prices_note.id()], &mut ctx.naming).await?;
+
+    let mut expected_revenue = 0;
+    for (note, price) in notes {
+        execute_note(&mut chain, note.id(), &mut ctx.naming).await?;
+        expected_revenue += price;
+
+        let total_revenue_slot = ctx.naming.storage().get_map_item(10, Word::new([Felt::new(ctx.fungible_asset.faucet_id().suffix().as_int()), Felt::new(ctx.fungible_asset.faucet_id().prefix().as_u64()), Felt::new(0), Felt::new(0)]))?;
+        assert_eq!(total_revenue_slot.first().unwrap().as_int(), expected_revenue);
+    }
+
+    let total_domain_count = ctx.naming.storage().get_item(9)?;
+    assert_eq!(total_domain_count.first().unwrap().as_int(), MAX_DOMAIN_LENGTH as u64);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_register_long_domain_underpaid() -> anyhow::Result<()> {
+    let mut ctx = init_naming().await?;
+    let refund_serial = Word::new([Felt::new(11), Felt::new(12), Felt::new(13), Felt::new(14)]);
+
+    // 12 letters must cost the breakpoint price, not zero
+    let cost = FungibleAsset::new(ctx.fungible_asset.faucet_id(), 122)?;
+    let note = create_register_note(ctx.registrar_1.id(), ctx.naming.id(), ctx.fungible_asset.faucet_id(), encode_domain("testtesttest"), 1, None, NoteAssets::new(vec![cost.into()])?, refund_serial).await?;
+    add_note_to_builder(&mut ctx.builder, note.clone())?;
+
+    let mut chain = execute_notes_and_build_chain(ctx.builder, &[ctx.initialize_note.id(), ctx.set_prices_note.id()], &mut ctx.naming).await?;
+    let result = execute_note(&mut chain, note.id(), &mut ctx.naming).await;
+
+    assert!(result.is_err(), "Expected revert but succeeded.");
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_set_prices_updates_prices() -> anyhow::Result<()> {
+    let mut ctx = init_naming().await?;
+    let second_token: AccountId = ACCOUNT_ID_PUBLIC_FUNGIBLE_FAUCET_2.try_into()?;
+    let new_prices = [(1, ctx.fungible_asset.faucet_id(), 999), (3, second_token, 321), (5, second_token, 42)];
+
+    let set_prices_note = create_set_prices_note(ctx.owner.id(), ctx.naming.id(), &new_prices).await?;
+    add_note_to_builder(&mut ctx.builder, set_prices_note.clone())?;
+
+    execute_notes_and_build_chain(ctx.builder, &[ctx.initialize_note.id(), ctx.set_prices_note.id(), set_prices_note.id()], &mut ctx.naming).await?;
+
+    for (letter_count, token, price) in new_prices {
+        let price_slot = ctx.naming.storage().get_map_item(2, Word::new([token.suffix(), token.prefix().as_felt(), Felt::new(letter_count as u64), Felt::new(0)]))?;
+        assert_eq!(price_slot.first().unwrap().as_int(), price);
+    }
+
+    // Untouched entries keep their previous price
+    let two_letter_slot = ctx.naming.storage().get_map_item(2, Word::new([ctx.fungible_asset.faucet_id().suffix(), ctx.fungible_asset.faucet_id().prefix().as_felt(), Felt::new(2), Felt::new(0)]))?;
+    assert_eq!(two_letter_slot.first().unwrap().as_int(), get_test_prices()[2].as_int());
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_set_prices_by_not_owner() -> anyhow::Result<()> {
+    let mut ctx = init_naming().await?;
+
+    let set_prices_note = create_set_prices_note(ctx.registrar_1.id(), ctx.naming.id(), &[(1, ctx.fungible_asset.faucet_id(), 1)]).await?;
+    add_note_to_builder(&mut ctx.builder, set_prices_note.clone())?;
+
+    let mut chain = execute_notes_and_build_chain(ctx.builder, &[ctx.initialize_note.id(), ctx.set_prices_note.id()], &mut ctx.naming).await?;
+    let result = execute_note(&mut chain, set_prices_note.id(), &mut ctx.naming).await;
+
+    assert!(result.is_err(), "Expected revert but succeeded.");
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_register_zero_price_rejected() -> anyhow::Result<()> {
+    let mut ctx = init_naming().await?;
+    let refund_serial = Word::new([Felt::new(11), Felt::new(12), Felt::new(13), Felt::new(14)]);
+
+    let set_prices_note = create_set_prices_note(ctx.owner.id(), ctx.naming.id(), &[(3, ctx.fungible_asset.faucet_id(), 0)]).await?;
+    let cost = FungibleAsset::new(ctx.fungible_asset.faucet_id(), 1)?;
+    let register_note = create_register_note(ctx.registrar_1.id(), ctx.naming.id(), ctx.fungible_asset.faucet_id(), encode_domain("abc"), 1, None, NoteAssets::new(vec![cost.into()])?, refund_serial).await?;
+    add_note_to_builder(&mut ctx.builder, set_prices_note.clone())?;
+    add_note_to_builder(&mut ctx.builder, register_note.clone())?;
+
+    let mut chain = execute_notes_and_build_chain(ctx.builder, &[ctx.initialize_note.id(), ctx.set_prices_note.id(), set_prices_note.id()], &mut ctx.naming).await?;
+    let result = execute_note(&mut chain, register_note.id(), &mut ctx.naming).await;
+
+    assert!(result.is_err(), "Expected registration with a zero price to fail, but it succeeded");
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_register_malformed_domain_words() -> anyhow::Result<()> {
+    let mut ctx = init_naming().await?;
+    let refund_serial = Word::new([Felt::new(11), Felt::new(12), Felt::new(13), Felt::new(14)]);
+    let with_length = |word: Word, len: u64| Word::new([word[0], word[1], word[2], Felt::new(len)]);
+
+    let second_felt_early = [vec![1, 2, 3, 0, 0, 0, 0], vec![4]].concat();
+    let third_felt_early = [vec![1; 7], vec![2, 0, 0, 0, 0, 0, 0], vec![3]].concat();
+    let only_last_felt = [vec![0; 14], vec![1, 2, 3]].concat();
+    let v2_bad_char = [vec![1; 22], vec![37]].concat();
+    let v2_bad_split_char = [vec![1; 5], vec![37], vec![1; 16]].concat();
+    let v2_empty_split_char = [vec![1; 5], vec![0], vec![1; 17]].concat();
+    let v2_word = encode_domain("a".repeat(25));
+    let cases = [
+        ("char code above 36", unsafe_encode_domain_codes(&[37])),
+        ("char code 255", unsafe_encode_domain_codes(&[1, 255, 2])),
+        ("empty char inside felt", with_length(unsafe_encode_domain_codes(&[1, 0, 2]), 2)),
+        ("leading empty char", with_length(unsafe_encode_domain_codes(&[0, 1]), 1)),
+        ("8 chars in one felt", Word::new([Felt::new(0), Felt::new(0), Felt::new(0x0101010101010101), Felt::new(8)])),
+        ("second felt before first is full", with_length(unsafe_encode_domain_codes(&second_felt_early), 4)),
+        ("third felt before second is full", with_length(unsafe_encode_domain_codes(&third_felt_early), 9)),
+        ("chars only in last felt", with_length(unsafe_encode_domain_codes(&only_last_felt), 3)),
+        ("length above char count", with_length(unsafe_encode_domain("abc"), 4)),
+        ("v2 encoding of a v1 name", encode_domain_v2("alice")),
+        ("v2 name above 30 chars", unsafe_encode_domain_codes_v2(&[1; 31])),
+        ("v2 char code above 36", unsafe_encode_domain_codes_v2(&v2_bad_char)),
+        ("v2 split char above 36", unsafe_encode_domain_codes_v2(&v2_bad_split_char)),
+        ("v2 empty split char", with_length(unsafe_encode_domain_codes_v2(&v2_empty_split_char), 256 + 22)),
+        ("v2 bits above 10 chars", Word::new([v2_word[0], v2_word[1], Felt::new(v2_word[2].as_int() | 1u64 << 60), v2_word[3]])),
+        ("unknown encoding version", with_length(v2_word, 2 * 256 + 25)),
+    ];
+
+    let mut notes = Vec::new();
+    for (case, domain) in cases {
+        // Pay the highest price so an accepted word could never fail on payment
+        let cost = FungibleAsset::new(ctx.fungible_asset.faucet_id(), 123123)?;
+        let note = create_register_note(ctx.registrar_1.id(), ctx.naming.id(), ctx.fungible_asset.faucet_id(), domain, 1, None, NoteAssets::new(vec![cost.into()])?, refund_serial).await?;
+        add_note_to_builder(&mut ctx.builder, note.clone())?;
+        notes.push((case, note));
+    }
+
+    let mut chain = execute_notes_and_build_chain(ctx.builder, &[ctx.initialize_note.id(), ctx.set_prices_note.id()], &mut ctx.naming).await?;
+
+    for (case, note) in notes {
+        let result = execute_note(&mut chain, note.id(), &mut ctx.naming).await;
+        assert!(result.is_err(), "Expected malformed domain ({}) to be rejected, but it was registered", case);
+    }
+    Ok(())
+}
@@ -0,0 +1,178 @@
+mod test_utils;
+
+use miden_client::{asset::FungibleAsset, note::{Note, NoteAssets, NoteInputs, NoteType}};
+use miden_crypto::{Felt, Word};
+use miden_testing::MockChain;
+use midenname_contracts::{domain::encode_domain, notes::{create_p2n_note, create_p2ne_note, create_register_note}};
+use test_utils::{init_naming, TestingContext};
+
+use crate::test_utils::{add_note_to_builder, create_note_for_naming, create_p2id_note_exact, execute_note, execute_note_with_foreign_naming, execute_notes_and_build_chain, get_foreign_account_inputs};
+
+// Registers "test" for one year from registrar_1, links it to registrar_1 and adds `p2n_note` to the chain
+async fn setup_p2n(ctx: &mut TestingContext, p2n_note: &Note) -> anyhow::Result<MockChain> {
+    let refund_serial = Word::new([Felt::new(11), Felt::new(12), Felt::new(13), Felt::new(14)]);
+    let domain_word = encode_domain("test");
+
+    let cost = FungibleAsset::new(ctx.fungible_asset.faucet_id(), 555)?;
+    let register_note = create_register_note(ctx.registrar_1.id(), ctx.naming.id(), ctx.fungible_asset.faucet_id(), domain_word, 1, None, NoteAssets::new(vec![cost.into()])?, refund_serial).await?;
+    let activate_note = create_note_for_naming("activate_domain".to_string(), NoteInputs::new(domain_word.to_vec())?, ctx.registrar_1.id(), ctx.naming.id(), NoteAssets::new(vec![])?).await?;
+    add_note_to_builder(&mut ctx.builder, register_note.clone())?;
+    add_note_to_builder(&mut ctx.builder, activate_note.clone())?;
+    add_note_to_builder(&mut ctx.builder, p2n_note.clone())?;
+
+    let builder = std::mem::replace(&mut ctx.builder, MockChain::builder());
+    execute_notes_and_build_chain(builder, &[ctx.initialize_note.id(), ctx.set_prices_note.id(), register_note.id(), activate_note.id()], &mut ctx.naming).await
+}
+
+async fn create_test_p2n_note(ctx: &TestingContext, name: &str) -> anyhow::Result<Note> {
+    let payment = FungibleAsset::new(ctx.fungible_asset.faucet_id(), 100)?;
+    create_p2n_note(ctx.registrar_2.id(), ctx.naming.id(), encode_domain(name), NoteAssets::new(vec![payment.into()])?).await
+}
+
+const RECLAIM_BLOCK_HEIGHT: u32 = 50;
+
+async fn create_test_p2ne_note(ctx: &TestingContext, name: &str) -> anyhow::Result<Note> {
+    let payment = FungibleAsset::new(ctx.fungible_asset.faucet_id(), 100)?;
+    create_p2ne_note(ctx.registrar_2.id(), ctx.naming.id(), encode_domain(name), NoteAssets::new(vec![payment.into()])?, RECLAIM_BLOCK_HEIGHT).await
+}
+
+#[tokio::test]
+async fn test_p2n_consumed_by_resolved_account() -> anyhow::Result<()> {
+    let mut ctx = init_naming().await?;
+    let p2n_note = create_test_p2n_note(&ctx, "test").await?;
+    let mut chain = setup_p2n(&mut ctx, &p2n_note).await?;
+    let balance_before = ctx.registrar_1.vault().get_balance(ctx.fungible_asset.faucet_id())?;
+
+    execute_note_with_foreign_naming(&mut chain, p2n_note.id(), &mut ctx.registrar_1, &ctx.naming).await?;
+
+    assert_eq!(ctx.registrar_1.vault().get_balance(ctx.fungible_asset.faucet_id())?, balance_before + 100);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_p2n_unregistered_name() -> anyhow::Result<()> {
+    let mut ctx = init_naming().await?;
+    let p2n_note = create_test_p2n_note(&ctx, "other").await?;
+    let mut chain = setup_p2n(&mut ctx, &p2n_note).await?;
+
+    let result = execute_note_with_foreign_naming(&mut chain, p2n_note.id(), &mut ctx.registrar_1, &ctx.naming).await;
+
+    assert!(result.is_err(), "Expected P2N to an unregistered name to fail, but it succeeded");
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_p2n_expired_name() -> anyhow::Result<()> {
+    let mut ctx = init_naming().await?;
+    let p2n_note = create_test_p2n_note(&ctx, "test").await?;
+    let mut chain = setup_p2n(&mut ctx, &p2n_note).await?;
+    chain.prove_until_block(100)?;
+
+    let result = execute_note_with_foreign_naming(&mut chain, p2n_note.id(), &mut ctx.registrar_1, &ctx.naming).await;
+
+    assert!(result.is_err(), "Expected P2N to an expired name to fail, but it succeeded");
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_p2n_wrong_consumer() -> anyhow::Result<()> {
+    let mut ctx = init_naming().await?;
+    let p2n_note = create_test_p2n_note(&ctx, "test").await?;
+    let mut chain = setup_p2n(&mut ctx, &p2n_note).await?;
+
+    let result = execute_note_with_foreign_naming(&mut chain, p2n_note.id(), &mut ctx.registrar_3, &ctx.naming).await;
+
+    assert!(result.is_err(), "Expected P2N consumed by another account to fail, but it succeeded");
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_p2ne_consumed_by_resolved_account() -> anyhow::Result<()> {
+    let mut ctx = init_naming().await?;
+    let p2ne_note = create_test_p2ne_note(&ctx, "test").await?;
+    let mut chain = setup_p2n(&mut ctx, &p2ne_note).await?;
+    let balance_before = ctx.registrar_1.vault().get_balance(ctx.fungible_asset.faucet_id())?;
+
+    execute_note_with_foreign_naming(&mut chain, p2ne_note.id(), &mut ctx.registrar_1, &ctx.naming).await?;
+
+    assert_eq!(ctx.registrar_1.vault().get_balance(ctx.fungible_asset.faucet_id())?, balance_before + 100);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_p2ne_reclaimed_by_sender() -> anyhow::Result<()> {
+    let mut ctx = init_naming().await?;
+    let p2ne_note = create_test_p2ne_note(&ctx, "test").await?;
+    let mut chain = setup_p2n(&mut ctx, &p2ne_note).await?;
+    chain.prove_until_block(RECLAIM_BLOCK_HEIGHT)?;
+    let balance_before = ctx.registrar_2.vault().get_balance(ctx.fungible_asset.faucet_id())?;
+
+    // the reclaim path does not read the registry
+    execute_note(&mut chain, p2ne_note.id(), &mut ctx.registrar_2).await?;
+
+    assert_eq!(ctx.registrar_2.vault().get_balance(ctx.fungible_asset.faucet_id())?, balance_before + 100);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_p2ne_reclaimed_by_sender_after_name_expired() -> anyhow::Result<()> {
+    let mut ctx = init_naming().await?;
+    let p2ne_note = create_test_p2ne_note(&ctx, "test").await?;
+    let mut chain = setup_p2n(&mut ctx, &p2ne_note).await?;
+    chain.prove_until_block(100)?;
+
+    let result = execute_note_with_foreign_naming(&mut chain, p2ne_note.id(), &mut ctx.registrar_1, &ctx.naming).await;
+    assert!(result.is_err(), "Expected P2NE to an expired name to fail, but it succeeded");
+
+    let balance_before = ctx.registrar_2.vault().get_balance(ctx.fungible_asset.faucet_id())?;
+    execute_note(&mut chain, p2ne_note.id(), &mut ctx.registrar_2).await?;
+
+    assert_eq!(ctx.registrar_2.vault().get_balance(ctx.fungible_asset.faucet_id())?, balance_before + 100);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_p2ne_reclaim_before_block_height() -> anyhow::Result<()> {
+    let mut ctx = init_naming().await?;
+    let p2ne_note = create_test_p2ne_note(&ctx, "test").await?;
+    let mut chain = setup_p2n(&mut ctx, &p2ne_note).await?;
+
+    let result = execute_note_with_foreign_naming(&mut chain, p2ne_note.id(), &mut ctx.registrar_2, &ctx.naming).await;
+
+    assert!(result.is_err(), "Expected P2NE reclaim before the reclaim block height to fail, but it succeeded");
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_p2ne_reclaim_by_other_account() -> anyhow::Result<()> {
+    let mut ctx = init_naming().await?;
+    let p2ne_note = create_test_p2ne_note(&ctx, "test").await?;
+    let mut chain = setup_p2n(&mut ctx, &p2ne_note).await?;
+    chain.prove_until_block(RECLAIM_BLOCK_HEIGHT)?;
+
+    let result = execute_note_with_foreign_naming(&mut chain, p2ne_note.id(), &mut ctx.registrar_3, &ctx.naming).await;
+
+    assert!(result.is_err(), "Expected P2NE consumed by an account other than the sender to fail, but it succeeded");
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_p2ne_reclaim_by_other_account_batched_behind_own_note() -> anyhow::Result<()> {
+    let mut ctx = init_naming().await?;
+    let own_serial = Word::new([Felt::new(21), Felt::new(22), Felt::new(23), Felt::new(24)]);
+    let own_note = create_p2id_note_exact(ctx.registrar_3.id(), ctx.registrar_3.id(), vec![], NoteType::Public, Felt::new(0), own_serial)?;
+    add_note_to_builder(&mut ctx.builder, own_note.clone())?;
+    let p2ne_note = create_test_p2ne_note(&ctx, "test").await?;
+    let mut chain = setup_p2n(&mut ctx, &p2ne_note).await?;
+    chain.prove_until_block(RECLAIM_BLOCK_HEIGHT)?;
+
+    // registrar_3 sent the first input note itself, which must not make it the sender of the P2NE note
+    let foreign_account_inputs = get_foreign_account_inputs(&chain, ctx.naming.id())?;
+    let tx_ctx = chain.build_tx_context(ctx.registrar_3.id(), &[own_note.id(), p2ne_note.id()], &[])?
+        .foreign_accounts(vec![foreign_account_inputs])
+        .build()?;
+    let result = tx_ctx.execute().await;
+
+    assert!(result.is_err(), "Expected P2NE reclaim batched behind another sender's note to fail, but it succeeded");
+    Ok(())
+}
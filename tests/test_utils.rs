@@ -5,14 +5,16 @@ use miden_assembly::{Assembler, DefaultSourceManager, Library, LibraryPath, ast:
 use miden_client::{ScriptBuilder, account::{Account, AccountBuilder, AccountId, AccountStorageMode}, asset::{Asset, FungibleAsset}, note::{Note, NoteAssets, NoteExecutionHint, NoteId, NoteInputs, NoteMetadata, NoteRecipient, NoteTag, NoteType}, testing::account_id::ACCOUNT_ID_PUBLIC_FUNGIBLE_FAUCET_1, transaction::OutputNote};
 use miden_crypto::{Felt, Word};
 use miden_lib::{account::auth, note::WellKnownNote, transaction::TransactionKernel};
-use miden_objects::account::AccountComponent;
+use miden_objects::{account::{AccountComponent, StorageSlot}, block::AccountWitness, transaction::ExecutedTransaction};
 use miden_testing::{Auth, MockChain, MockChainBuilder, TransactionContextBuilder};
-use midenname_contracts::storage::naming_storage;
+use midenname_contracts::{config::RegistryParameters, notes::{build_initialize_inputs, create_set_prices_note}, storage::{commitment_ages, naming_storage, DEFAULT_MAX_COMMITMENT_AGE}};
 use rand::{Rng, SeedableRng};
 use rand_chacha::ChaCha20Rng;
 
 pub fn create_test_naming_account() -> Account {
-    let storage_slots = naming_storage();
+    let mut storage_slots = naming_storage();
+    // A zero min commitment age opens plain registration, naming_commitment_tests set their own ages
+    storage_slots[28] = StorageSlot::Value(commitment_ages(0, DEFAULT_MAX_COMMITMENT_AGE));
     let code = fs::read_to_string(Path::new("./masm/accounts/naming.masm")).unwrap();
 
     let component = AccountComponent::compile(
@@ -98,6 +100,10 @@ pub fn get_test_prices() -> Vec<Felt> {
     vec![Felt::new(0), Felt::new(123123), Felt::new(45645), Felt::new(789), Felt::new(555), Felt::new(123)]
 }
 
+pub fn get_test_price_entries(token: AccountId) -> Vec<(u8, AccountId, u64)> {
+    get_test_prices().iter().enumerate().skip(1).map(|(letter_count, price)| (letter_count as u8, token, price.as_int())).collect()
+}
+
 pub struct TestingContext {
     pub builder: MockChainBuilder,
     //pub chain: MockChain,
@@ -105,6 +111,8 @@ pub struct TestingContext {
     pub registrar_1: Account,
     pub registrar_2: Account,
     pub registrar_3: Account,
+    #[allow(dead_code)]
+    pub fpi_reader: Account,
     pub naming: Account,
     pub fungible_asset: FungibleAsset,
     pub one_year: u32,
@@ -122,39 +130,27 @@ pub async fn init_naming() -> anyhow::Result<TestingContext> {
     let domain_registrar_account = builder.add_existing_wallet_with_assets(Auth::BasicAuth, vec![fungible_asset_1.into()])?;
     let domain_registrar_account_2 = builder.add_existing_wallet_with_assets(Auth::BasicAuth, vec![fungible_asset_2.into()])?;
     let domain_registrar_account_3 = builder.add_existing_wallet_with_assets(Auth::BasicAuth, vec![fungible_asset_3.into()])?;
+    let fpi_reader_account = builder.add_existing_wallet(Auth::IncrNonce)?;
     let mut naming_account = create_test_naming_account();
     builder.add_account(naming_account.clone())?;
     //let mut mockchain = builder.build()?;
     let one_year_time: u32 = 500;
 
-    let initialize_inputs = NoteInputs::new([
-        Felt::new(owner_account.id().suffix().into()),
-        Felt::new(owner_account.id().prefix().into()),
-        Felt::new(0),
-        Felt::new(0),
-        Felt::new(one_year_time.into()),
-        Felt::new(0),
-        Felt::new(0),
-        Felt::new(0),
-    ].to_vec())?;
+    let initialize_inputs = NoteInputs::new(build_initialize_inputs(owner_account.id(), RegistryParameters::with_one_year(one_year_time)))?;
     let init_note = create_note_for_naming("initialize_naming".to_string(), initialize_inputs, owner_account.id(), naming_account.id(), NoteAssets::new(vec![]).unwrap()).await?;
     
     //execute_note(&mut mockchain, init_note, &mut naming_account).await?;
     add_note_to_builder(&mut builder, init_note.clone())?;
     // Set prices
 
-    let note_inputs = NoteInputs::new([
-            Felt::new(fungible_asset_1.faucet_id().suffix().into()),
-            Felt::new(fungible_asset_1.faucet_id().prefix().into()),
-        ].to_vec())?;
-    let set_prices_note = create_note_for_naming("set_all_prices".to_string(), note_inputs, owner_account.id(), naming_account.id(), NoteAssets::new(vec![]).unwrap()).await?;
+    let set_prices_note = create_set_prices_note(owner_account.id(), naming_account.id(), &get_test_price_entries(fungible_asset_1.faucet_id())).await?;
 
     add_note_to_builder(&mut builder, set_prices_note.clone())?;
     //set_test_prices(&mut mockchain, owner_account.id(), &mut naming_account, fungible_asset_1.faucet_id()).await?;
     //add_set_prices_notes(&mut builder,owner_account.id(), &mut naming_account, fungible_asset_1.faucet_id()).await?;
 
     Ok(TestingContext { builder: builder, owner: owner_account, registrar_1: domain_registrar_account, 
-        registrar_2: domain_registrar_account_2, registrar_3: domain_registrar_account_3, naming: naming_account, 
+        registrar_2: domain_registrar_account_2, registrar_3: domain_registrar_account_3, fpi_reader: fpi_reader_account, naming: naming_account, 
         fungible_asset: fungible_asset_1, one_year: one_year_time, initialize_note: init_note, set_prices_note: set_prices_note })
 }
 
@@ -186,7 +182,106 @@ pub async fn execute_note(chain: &mut MockChain, note_id: NoteId, target: &mut A
     Ok(())
 }
 
+// Same as execute_note, but lets the executor know the full details of notes the registry is expected to create
+#[allow(dead_code)]
+pub async fn execute_note_with_expected_outputs(chain: &mut MockChain, note_id: NoteId, target: &mut Account, expected_notes: Vec<Note>) -> anyhow::Result<ExecutedTransaction> {
+    let tx_ctx = chain.build_tx_context(target.id(), &[note_id], &[])?
+        .extend_expected_output_notes(expected_notes.into_iter().map(OutputNote::Full).collect())
+        .build()?;
+
+    let executed_tx = tx_ctx.execute().await?;
+
+    target.apply_delta(&executed_tx.account_delta())?;
+    chain.add_pending_executed_transaction(&executed_tx)?;
+    chain.prove_next_block()?;
+
+    Ok(executed_tx)
+}
+
+// Same as execute_note, with the registry available for foreign procedure calls
+#[allow(dead_code)]
+pub async fn execute_note_with_foreign_naming(chain: &mut MockChain, note_id: NoteId, target: &mut Account, naming: &Account) -> anyhow::Result<()> {
+    let foreign_account_inputs = get_foreign_account_inputs(chain, naming.id())?;
+    let tx_ctx = chain.build_tx_context(target.id(), &[note_id], &[])?
+        .foreign_accounts(vec![foreign_account_inputs])
+        .build()?;
 
+    let executed_tx = tx_ctx.execute().await?;
+
+    target.apply_delta(&executed_tx.account_delta())?;
+    chain.add_pending_executed_transaction(&executed_tx)?;
+    chain.prove_next_block()?;
+
+    Ok(())
+}
+
+// Proves blocks until the latest block timestamp reaches `timestamp`
+#[allow(dead_code)]
+pub fn advance_to_timestamp(chain: &mut MockChain, timestamp: u64) -> anyhow::Result<()> {
+    while (chain.latest_block_header().timestamp() as u64) < timestamp {
+        chain.prove_next_block()?;
+    }
+    Ok(())
+}
+
+// Lays out words as they sit on the stack, first word on top
+#[allow(dead_code)]
+pub fn words_to_stack(words: &[Word]) -> Vec<Felt> {
+    words.iter().flat_map(|word| word.iter().rev().copied().collect::<Vec<Felt>>()).collect()
+}
+
+// Committed state of `account_id` and its account tree witness, as needed for foreign procedure calls
+#[allow(dead_code)]
+pub fn get_foreign_account_inputs(chain: &MockChain, account_id: AccountId) -> anyhow::Result<(Account, AccountWitness)> {
+    let account = chain.committed_account(account_id)?.clone();
+    let witness = chain.account_witnesses([account_id]).remove(&account_id)
+        .ok_or_else(|| anyhow::anyhow!("missing account witness for {account_id}"))?;
+
+    Ok((account, witness))
+}
+
+// Calls a read-only naming procedure through FPI from `reader` and asserts its outputs.
+// Inputs and outputs are listed from the top of the stack.
+#[allow(dead_code)]
+pub async fn execute_naming_fpi_call(chain: &MockChain, reader: &Account, naming: &Account, procedure: &str, inputs: &[Felt], expected_outputs: &[Felt]) -> anyhow::Result<()> {
+    let padding = "push.0 ".repeat(16 - inputs.len());
+    let push_inputs: String = inputs.iter().rev().map(|felt| format!("push.{} ", felt.as_int())).collect();
+    let assert_outputs: String = expected_outputs.iter().map(|felt| format!("push.{} assert_eq\n", felt.as_int())).collect();
+    let script_code = format!("
+use.miden::tx
+use.miden_name::naming
+use.std::sys
+
+begin
+    {padding}
+    {push_inputs}
+    procref.naming::{procedure}
+    push.{naming_suffix}.{naming_prefix}
+    exec.tx::execute_foreign_procedure
+    {assert_outputs}
+    exec.sys::truncate_stack
+end
+",
+        naming_suffix = naming.id().suffix().as_int(),
+        naming_prefix = naming.id().prefix().as_u64(),
+    );
+
+    let naming_code = fs::read_to_string(Path::new("./masm/accounts/naming.masm"))?;
+    let library = create_library(naming_code, "miden_name::naming")?;
+    let tx_script = ScriptBuilder::new(true)
+        .with_dynamically_linked_library(&library)?
+        .compile_tx_script(script_code)?;
+
+    let foreign_account_inputs = get_foreign_account_inputs(chain, naming.id())?;
+    let tx_ctx = chain.build_tx_context(reader.id(), &[], &[])?
+        .foreign_accounts(vec![foreign_account_inputs])
+        .tx_script(tx_script)
+        .build()?;
+
+    tx_ctx.execute().await?;
+
+    Ok(())
+}
 
 fn create_library(account_code: String, library_path: &str) -> anyhow::Result<Library> {
     let assembler: Assembler = TransactionKernel::assembler().with_debug_mode(true);